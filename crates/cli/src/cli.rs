@@ -1,5 +1,10 @@
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
 use directories::ProjectDirs;
+use kakei_processor::{chart::ChartKind, encoding::Encoding, gains::CostMethod};
+use kakei_types::{
+    currency::CurrencyCode,
+    date::{Date, DateError},
+};
 use std::{path::PathBuf, sync::LazyLock};
 
 #[derive(Debug, Parser)]
@@ -11,6 +16,393 @@ pub struct CLIArgs {
     /// kakei's config file path
     #[arg(short, long, default_value = DEFAULT_CONFIG_PATH.display().to_string())]
     pub config: PathBuf,
+
+    /// Encoding of the note: utf-8, shift_jis or euc-jp. Detected from each file by default
+    #[arg(long)]
+    pub encoding: Option<Encoding>,
+
+    /// Report to show. Defaults to `balance`
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Show the balance of each account
+    Balance(BalanceArgs),
+
+    /// Manage the price database
+    Prices(PricesArgs),
+
+    /// Show realized and unrealized capital gains in a year
+    Gains(GainsArgs),
+
+    /// Show budget against actual spending in the current period
+    Budget(BudgetArgs),
+
+    /// Reflect on a month with the kakeibo method: income, savings, spending by pillar
+    Reflect(ReflectArgs),
+
+    /// Manage recurring transactions
+    Recur(RecurArgs),
+
+    /// Format the note and the files it includes
+    Fmt(FmtArgs),
+
+    /// Add a transaction to the note interactively
+    Add(AddArgs),
+
+    /// Run a language server for the note over stdio
+    Lsp,
+
+    /// Project asset balances month by month from recurring transactions and budgets
+    Forecast(ForecastArgs),
+
+    /// Browse accounts and their postings in a full-screen terminal UI
+    Tui,
+
+    /// Draw spending, monthly income and expenses, or asset balances as a chart
+    Chart(ChartArgs),
+
+    /// Write a static HTML site of the note with registers, monthly reports and charts
+    Html(HtmlArgs),
+
+    /// Serve a read-only JSON API and a dashboard, reloading the note when it changes
+    Serve(ServeArgs),
+
+    /// Read bank and card statements into transactions
+    Import(ImportArgs),
+
+    /// Write the note in the format of another accounting tool
+    Export(ExportArgs),
+}
+
+impl Default for Command {
+    fn default() -> Self {
+        Self::Balance(BalanceArgs::default())
+    }
+}
+
+#[derive(Debug, Clone, Args, Default)]
+pub struct BalanceArgs {
+    /// Convert every balance into this currency, such as JPY
+    #[arg(long)]
+    pub value_in: Option<CurrencyCode>,
+
+    /// Report date in YYYY-MM-DD. Defaults to today
+    #[arg(long)]
+    pub date: Option<Date>,
+
+    /// Redraw the report whenever the note or a file it includes changes
+    #[arg(long)]
+    pub watch: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct PricesArgs {
+    #[command(subcommand)]
+    pub command: PricesCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PricesCommand {
+    /// Import date,base,quote,rate CSV files into a canonical price file
+    Import(PricesImportArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct PricesImportArgs {
+    /// CSV files to import
+    #[arg(required = true)]
+    pub files: Vec<PathBuf>,
+
+    /// Price file to merge into. Prints to stdout when omitted
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct GainsArgs {
+    /// Year of the report. Defaults to this year
+    #[arg(long)]
+    pub year: Option<i32>,
+
+    /// moving-average (移動平均法) or total-average (総平均法)
+    #[arg(long, default_value_t = CostMethod::default())]
+    pub method: CostMethod,
+
+    /// Currency held as an asset
+    #[arg(long, default_value = "SATS")]
+    pub currency: CurrencyCode,
+
+    /// Redraw the report whenever the note or a file it includes changes
+    #[arg(long)]
+    pub watch: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct BudgetArgs {
+    /// A date in the period to report, in YYYY-MM-DD. Defaults to today
+    #[arg(long)]
+    pub date: Option<Date>,
+
+    /// Redraw the report whenever the note or a file it includes changes
+    #[arg(long)]
+    pub watch: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ReflectArgs {
+    /// Month to reflect on, in YYYY-MM. Defaults to this month
+    #[arg(long, value_parser = parse_month)]
+    pub month: Option<Date>,
+
+    /// Redraw the report whenever the note or a file it includes changes
+    #[arg(long)]
+    pub watch: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct RecurArgs {
+    #[command(subcommand)]
+    pub command: RecurCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RecurCommand {
+    /// Show occurrences which are not written in the note yet
+    List(RecurListArgs),
+
+    /// Append occurrences which have happened to the note
+    Materialize(RecurMaterializeArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct RecurListArgs {
+    /// Last date to list, in YYYY-MM-DD. Defaults to today
+    #[arg(long)]
+    pub until: Option<Date>,
+}
+
+#[derive(Debug, Args)]
+pub struct RecurMaterializeArgs {
+    /// Last date to write, in YYYY-MM-DD. Defaults to today
+    #[arg(long)]
+    pub until: Option<Date>,
+
+    /// Print the transactions without writing them
+    #[arg(long, default_value_t = false)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct FmtArgs {
+    /// List the files which are not formatted instead of writing them, failing if any
+    #[arg(long, default_value_t = false)]
+    pub check: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct AddArgs {
+    /// One-line entry such as "lunch 980 food": [date] payee amount [currency] [account [paid-from]].
+    /// Prompts for the transaction without it
+    pub entry: Option<String>,
+
+    /// Print the transaction without writing it
+    #[arg(long, default_value_t = false)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ForecastArgs {
+    /// Last month to project, in YYYY-MM
+    #[arg(long, value_parser = parse_month)]
+    pub until: Date,
+
+    /// Date the projection starts from, in YYYY-MM-DD. Defaults to today
+    #[arg(long)]
+    pub from: Option<Date>,
+
+    /// Redraw the report whenever the note or a file it includes changes
+    #[arg(long)]
+    pub watch: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ChartArgs {
+    /// spending (by category in a month), monthly (income and expenses) or balances (of assets)
+    pub kind: ChartKind,
+
+    /// Last month to chart, in YYYY-MM. Defaults to this month
+    #[arg(long, value_parser = parse_month)]
+    pub month: Option<Date>,
+
+    /// Number of months in monthly and balances charts
+    #[arg(long, default_value_t = 12)]
+    pub months: i32,
+
+    /// Width of the chart. Defaults to the width of the terminal
+    #[arg(long)]
+    pub width: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+pub struct HtmlArgs {
+    /// Directory to write the site into, created if missing
+    #[arg(short, long)]
+    pub out: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    /// Address to listen on. Use 0.0.0.0:8080 to open it to the local network
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    pub bind: String,
+}
+
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    #[command(subcommand)]
+    pub command: ImportCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ImportCommand {
+    /// Read a CSV statement laid out as a profile in the configuration
+    Csv(ImportCsvArgs),
+
+    /// Read an OFX or QFX statement, finding the account of each statement under [import.accounts]
+    Ofx(ImportOfxArgs),
+
+    /// Read the transactions, prices and account directives of a ledger or hledger journal
+    Ledger(ImportLedgerArgs),
+
+    /// Read a CSV exported from Zaim, placing its categories and accounts by the mapping
+    Zaim(ImportAppArgs),
+
+    /// Read a CSV exported from MoneyForward ME, placing its categories and accounts by the
+    /// mapping
+    Moneyforward(ImportAppArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ImportCsvArgs {
+    /// The statement to read
+    pub file: PathBuf,
+
+    /// Name of the profile under [import.profiles] in the configuration
+    #[arg(long)]
+    pub profile: String,
+
+    /// Payee rules, instead of `rules` under [import] in the configuration
+    #[arg(long)]
+    pub rules: Option<PathBuf>,
+
+    /// Encoding of the statement: utf-8, shift_jis or euc-jp, instead of the profile's or a
+    /// detected one
+    #[arg(long)]
+    pub encoding: Option<Encoding>,
+
+    /// Append the transactions to the note instead of printing them
+    #[arg(long, default_value_t = false)]
+    pub append: bool,
+
+    /// Ask whether to import each duplicate of a transaction in the note, instead of skipping
+    /// the certain ones and listing the possible ones
+    #[arg(long, default_value_t = false)]
+    pub review: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ImportOfxArgs {
+    /// The statement to read
+    pub file: PathBuf,
+
+    /// Payee rules, instead of `rules` under [import] in the configuration
+    #[arg(long)]
+    pub rules: Option<PathBuf>,
+
+    /// Encoding of the statement: utf-8, shift_jis or euc-jp, instead of a detected one
+    #[arg(long)]
+    pub encoding: Option<Encoding>,
+
+    /// Append the transactions to the note instead of printing them
+    #[arg(long, default_value_t = false)]
+    pub append: bool,
+
+    /// Ask whether to import each duplicate of a transaction in the note, instead of skipping
+    /// the certain ones and listing the possible ones
+    #[arg(long, default_value_t = false)]
+    pub review: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ImportLedgerArgs {
+    /// The journal to read
+    pub file: PathBuf,
+
+    /// Encoding of the journal: utf-8, shift_jis or euc-jp, instead of a detected one
+    #[arg(long)]
+    pub encoding: Option<Encoding>,
+
+    /// Append the entries to the note instead of printing them
+    #[arg(long, default_value_t = false)]
+    pub append: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ImportAppArgs {
+    /// The CSV to read
+    pub file: PathBuf,
+
+    /// Categories and accounts of the app, instead of `mapping` under [import] in the
+    /// configuration
+    #[arg(long)]
+    pub mapping: Option<PathBuf>,
+
+    /// Encoding of the CSV: utf-8, shift_jis or euc-jp, instead of a detected one
+    #[arg(long)]
+    pub encoding: Option<Encoding>,
+
+    /// Also read records the app leaves out of its totals
+    #[arg(long, default_value_t = false)]
+    pub all: bool,
+
+    /// Append the transactions to the note instead of printing them
+    #[arg(long, default_value_t = false)]
+    pub append: bool,
+
+    /// Ask whether to import each duplicate of a transaction in the note, instead of skipping
+    /// the certain ones and listing the possible ones
+    #[arg(long, default_value_t = false)]
+    pub review: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    #[command(subcommand)]
+    pub command: ExportCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ExportCommand {
+    /// Write a Beancount ledger, for Fava and other Beancount tools
+    Beancount(ExportOutArgs),
+
+    /// Write a journal of ledger and hledger
+    Ledger(ExportOutArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ExportOutArgs {
+    /// File to write instead of printing
+    #[arg(short, long)]
+    pub out: Option<PathBuf>,
+}
+
+/// `2026-04` as the first day of the month
+fn parse_month(s: &str) -> Result<Date, DateError> {
+    format!("{s}-01").parse()
 }
 
 /// Default Configuration Path, using directories crate to calculate ProjectDirs (~/.config/kakei)
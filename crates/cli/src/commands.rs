@@ -0,0 +1,48 @@
+//! Subcommands of kakei CLI
+
+pub mod add;
+pub mod balance;
+pub mod budget;
+pub mod chart;
+pub mod export;
+pub mod fmt;
+pub mod forecast;
+pub mod gains;
+pub mod html;
+pub mod import;
+pub mod lsp;
+pub mod prices;
+pub mod recur;
+pub mod reflect;
+pub mod serve;
+pub mod tui;
+pub mod watch;
+
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Appends `entries`, such as transactions, to the end of the note, each after a blank line.
+///
+/// The note is replaced at once by renaming a temporary file, so an interrupted write never
+/// leaves half an entry behind.
+pub(crate) fn append<T: std::fmt::Display>(kakeibo: &Path, entries: &[T]) -> std::io::Result<()> {
+    let kakeibo: PathBuf = std::fs::canonicalize(kakeibo)?;
+    let mut text: String = std::fs::read_to_string(&kakeibo)?;
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    for entry in entries {
+        text.push_str(&format!("\n{entry}"));
+    }
+
+    let name = kakeibo.file_name().unwrap_or_default().to_string_lossy();
+    let temporary: PathBuf = kakeibo.with_file_name(format!(".{name}.tmp"));
+    let mut file = std::fs::File::create(&temporary)?;
+    file.write_all(text.as_bytes())?;
+    file.sync_all()?;
+    file.set_permissions(std::fs::metadata(&kakeibo)?.permissions())?;
+
+    std::fs::rename(&temporary, &kakeibo)
+}
@@ -0,0 +1,251 @@
+use crate::cli::AddArgs;
+use inquire::{
+    Autocomplete, Confirm, CustomType, CustomUserError, Text, autocompletion::Replacement,
+    validator::Validation,
+};
+use kakei_processor::Processor;
+use kakei_types::{
+    currency::Amount,
+    date::Date,
+    entry::{Entry, Posting, Transaction},
+};
+use std::path::Path;
+
+pub fn run(processor: &Processor, kakeibo: &Path, args: AddArgs) -> anyhow::Result<()> {
+    match &args.entry {
+        Some(entry) => quick(processor, kakeibo, entry, args.dry_run),
+        None => interactive(processor, kakeibo, args.dry_run),
+    }
+}
+
+fn quick(processor: &Processor, kakeibo: &Path, entry: &str, dry_run: bool) -> anyhow::Result<()> {
+    let quick = kakei_parser::parse_quick(entry)?;
+    let transaction: Transaction = processor.complete_quick(&quick, Date::today())?;
+    check(processor, &transaction)?;
+
+    print!("{}", transaction);
+    if !dry_run {
+        super::append(kakeibo, &[transaction])?;
+    }
+
+    Ok(())
+}
+
+fn interactive(processor: &Processor, kakeibo: &Path, dry_run: bool) -> anyhow::Result<()> {
+    let accounts: Vec<String> = processor.accounts().into_iter().map(String::from).collect();
+    let payees: Vec<String> = processor.payees().into_iter().map(String::from).collect();
+
+    let date: Date = CustomType::new("Date:")
+        .with_default(Date::today())
+        .with_error_message("Please type a date in YYYY-MM-DD")
+        .prompt()?;
+    let payee: String = Text::new("Payee:")
+        .with_autocomplete(Completer(payees))
+        .with_validator(|payee: &str| Ok(validate_payee(payee)))
+        .prompt()?;
+
+    // The last transaction of the payee suggests the rest
+    let last = processor.last_transaction(payee.trim());
+    let last_posting = last.and_then(|t| t.postings.iter().find(|p| p.amount.is_some()));
+
+    let suggested_account = last_posting.map_or(String::new(), |p| p.account.clone());
+    let account: String = Text::new("Account:")
+        .with_autocomplete(Completer(accounts.clone()))
+        .with_validator(account_validator(&accounts))
+        .with_default(&suggested_account)
+        .prompt()?;
+
+    let mut amount = CustomType::<Amount>::new("Amount:")
+        .with_help_message("A number is JPY, such as 980 or 21000 SATS");
+    if let Some(suggested) = last_posting.and_then(|p| p.amount) {
+        amount = amount.with_default(suggested);
+    }
+    let amount: Amount = amount.prompt()?;
+
+    let suggested_from = processor
+        .paying_account(payee.trim(), account.trim())
+        .unwrap_or_default();
+    let from: String = Text::new("Paid from:")
+        .with_autocomplete(Completer(accounts.clone()))
+        .with_validator(account_validator(&accounts))
+        .with_default(&suggested_from)
+        .prompt()?;
+
+    let transaction: Transaction = transaction(date, &payee, &account, amount, &from);
+    check(processor, &transaction)?;
+
+    print!("\n{}", transaction);
+    if dry_run {
+        return Ok(());
+    }
+    let message = format!("Append to {}?", kakeibo.display());
+    if Confirm::new(&message).with_default(true).prompt()? {
+        super::append(kakeibo, &[transaction])?;
+    }
+
+    Ok(())
+}
+
+/// The answers of the prompts as a transaction paying `amount` to `account` from `from`
+fn transaction(date: Date, payee: &str, account: &str, amount: Amount, from: &str) -> Transaction {
+    Transaction {
+        date,
+        payee: payee.trim().to_string(),
+        postings: vec![
+            Posting {
+                account: account.trim().to_string(),
+                amount: Some(amount),
+                cost: None,
+            },
+            Posting {
+                account: from.trim().to_string(),
+                amount: None,
+                cost: None,
+            },
+        ],
+        metadata: Vec::new(),
+        generated: false,
+    }
+}
+
+fn validate_payee(payee: &str) -> Validation {
+    if payee.trim().is_empty() || payee.contains('"') {
+        Validation::Invalid("A payee must not be empty nor contain '\"'".into())
+    } else {
+        Validation::Valid
+    }
+}
+
+/// Accepts only the accounts of the note, as [`check`] does.
+fn account_validator(
+    accounts: &[String],
+) -> impl Fn(&str) -> Result<Validation, CustomUserError> + Clone + use<> {
+    let accounts: Vec<String> = accounts.to_vec();
+    move |account: &str| {
+        Ok(if accounts.iter().any(|known| known == account.trim()) {
+            Validation::Valid
+        } else {
+            Validation::Invalid(
+                "Not an account of the note. Write an `open` directive for a new account".into(),
+            )
+        })
+    }
+}
+
+/// Written entries must read back and post only to accounts already opened, budgeted or posted
+/// to in the note, so a typo in an account never reaches the note.
+fn check(processor: &Processor, transaction: &Transaction) -> anyhow::Result<()> {
+    let accounts: Vec<&str> = processor.accounts();
+    if let Some(posting) = transaction
+        .postings
+        .iter()
+        .find(|p| !accounts.contains(&p.account.as_str()))
+    {
+        anyhow::bail!(
+            "\"{}\" is not an account of the note, write an `open` directive for a new account",
+            posting.account
+        );
+    }
+
+    let entries: Vec<Entry> = kakei_parser::parse(&transaction.to_string())?;
+    Processor::from_entries(entries)?;
+    Ok(())
+}
+
+/// Completes case-insensitively from `0`, those starting with the input first.
+#[derive(Debug, Clone)]
+struct Completer(Vec<String>);
+
+impl Autocomplete for Completer {
+    fn get_suggestions(&mut self, input: &str) -> Result<Vec<String>, CustomUserError> {
+        let input = input.to_lowercase();
+        let (mut prefixed, contained): (Vec<String>, Vec<String>) = self
+            .0
+            .iter()
+            .filter(|candidate| candidate.to_lowercase().contains(&input))
+            .cloned()
+            .partition(|candidate| candidate.to_lowercase().starts_with(&input));
+
+        prefixed.extend(contained);
+        Ok(prefixed)
+    }
+
+    fn get_completion(
+        &mut self,
+        input: &str,
+        highlighted_suggestion: Option<String>,
+    ) -> Result<Replacement, CustomUserError> {
+        if highlighted_suggestion.is_some() {
+            return Ok(highlighted_suggestion);
+        }
+
+        let suggestions = self.get_suggestions(input)?;
+        Ok(match suggestions.as_slice() {
+            [only] => Some(only.clone()),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Completer, account_validator, check, transaction, validate_payee};
+    use inquire::{Autocomplete, CustomUserError, validator::Validation};
+    use kakei_processor::Processor;
+    use kakei_types::{
+        currency::{Amount, JPY},
+        date::Date,
+    };
+
+    const NOTE: &str = "\
+2026-04-01 open Assets:Wallet JPY
+2026-04-01 \"Matsuya\"
+  Expenses:Food  780 JPY
+  Assets:Bank
+";
+
+    #[test]
+    fn prompts() -> Result<(), CustomUserError> {
+        assert_eq!(validate_payee("Matsuya"), Validation::Valid);
+        assert!(matches!(validate_payee("  "), Validation::Invalid(_)));
+        assert!(matches!(validate_payee("\"Sukiya"), Validation::Invalid(_)));
+
+        let accounts: Vec<String> = ["Assets:Bank", "Assets:Wallet", "Expenses:Food"]
+            .map(String::from)
+            .to_vec();
+        let validate = account_validator(&accounts);
+        assert_eq!(validate(" Expenses:Food ")?, Validation::Valid);
+        assert!(matches!(validate("Expenses:Fod")?, Validation::Invalid(_)));
+
+        let mut completer = Completer(accounts);
+        assert_eq!(
+            completer.get_suggestions("a")?,
+            vec!["Assets:Bank", "Assets:Wallet"]
+        );
+        assert_eq!(completer.get_suggestions("wall")?, vec!["Assets:Wallet"]);
+        assert_eq!(
+            completer.get_completion("food", None)?,
+            Some("Expenses:Food".to_string())
+        );
+        assert_eq!(completer.get_completion("assets", None)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn check_accounts() -> anyhow::Result<()> {
+        let processor = Processor::from_entries(kakei_parser::parse(NOTE)?)?;
+        let date = Date::new(2026, 4, 5)?;
+        let amount = Amount::JPY(JPY(980));
+
+        let written = transaction(date, " Matsuya ", "Expenses:Food", amount, "Assets:Wallet ");
+        assert_eq!(
+            written.to_string(),
+            "2026-04-05 \"Matsuya\"\n  Expenses:Food  980 JPY\n  Assets:Wallet\n"
+        );
+        check(&processor, &written)?;
+
+        let typo = transaction(date, "Matsuya", "Expenses:Fod", amount, "Assets:Wallet");
+        assert!(check(&processor, &typo).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+use crate::cli::BalanceArgs;
+use kakei_processor::Processor;
+use kakei_types::date::Date;
+
+pub fn run(processor: &Processor, args: BalanceArgs) -> anyhow::Result<()> {
+    let date: Date = args.date.unwrap_or_else(Date::today);
+    let processor: Processor = processor.until(date)?;
+
+    match args.value_in {
+        Some(quote) => {
+            println!("{}", processor.valuation_table(quote, date)?);
+            println!("Net worth: {}", processor.net_worth(quote, date)?);
+        }
+        None => {
+            for table in processor.tables() {
+                println!("{}", table);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,14 @@
+use crate::cli::BudgetArgs;
+use kakei_processor::Processor;
+use kakei_types::date::Date;
+use std::io::IsTerminal;
+
+pub fn run(processor: &Processor, args: BudgetArgs) -> anyhow::Result<()> {
+    let date: Date = args.date.unwrap_or_else(Date::today);
+
+    // Escape codes would garble the table piped to a file or another command
+    let color: bool = std::io::stdout().is_terminal();
+    println!("{}", processor.budget_table(date, color));
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use crate::cli::ChartArgs;
+use kakei_processor::{Processor, chart::ChartKind};
+use kakei_types::date::Date;
+
+pub fn run(processor: &Processor, args: ChartArgs) -> anyhow::Result<()> {
+    let today: Date = Date::today();
+    let month: Date = match args.month {
+        Some(month) => month,
+        None => Date::new(today.year(), today.month(), 1)?,
+    };
+    let width: usize = args.width.unwrap_or_else(terminal_width);
+    let first: Date = month.add_months(1 - args.months.max(1));
+    let end: Date = month.add_months(1);
+
+    let (title, chart) = match args.kind {
+        ChartKind::Spending => (
+            format!("Spending in {}", label(month)),
+            processor.spending_chart(month..end, width),
+        ),
+        ChartKind::Monthly => (
+            format!(
+                "Income and expenses from {} to {}",
+                label(first),
+                label(month)
+            ),
+            processor.monthly_chart(first..end, width),
+        ),
+        ChartKind::Balances => (
+            format!("Asset balances from {} to {}", label(first), label(month)),
+            processor.balance_chart(first..end, width),
+        ),
+    };
+
+    println!("{title}");
+    if chart.is_empty() {
+        println!("Nothing to chart, no JPY postings");
+    } else {
+        print!("{chart}");
+    }
+
+    Ok(())
+}
+
+fn label(month: Date) -> String {
+    format!("{:04}-{:02}", month.year(), month.month())
+}
+
+/// Columns of the terminal, or 80 when the output is not a terminal
+fn terminal_width() -> usize {
+    ratatui::crossterm::terminal::size()
+        .map(|(columns, _)| columns as usize)
+        .unwrap_or(80)
+}
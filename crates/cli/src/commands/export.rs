@@ -0,0 +1,26 @@
+use crate::cli::{ExportArgs, ExportCommand};
+use anyhow::Context;
+use kakei_processor::Processor;
+use std::path::PathBuf;
+
+pub fn run(processor: &Processor, args: ExportArgs) -> anyhow::Result<()> {
+    match args.command {
+        ExportCommand::Beancount(beancount_args) => {
+            write(&processor.beancount(), beancount_args.out)
+        }
+        ExportCommand::Ledger(ledger_args) => write(&processor.ledger(), ledger_args.out),
+    }
+}
+
+/// Prints `text`, or writes it to `out`.
+fn write(text: &str, out: Option<PathBuf>) -> anyhow::Result<()> {
+    match out {
+        Some(path) => {
+            std::fs::write(&path, text)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            eprintln!("Wrote {}", path.display());
+        }
+        None => print!("{text}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use crate::cli::FmtArgs;
+use anyhow::{Context, bail};
+use kakei_processor::{
+    Processor,
+    encoding::{self, Encoding},
+};
+use std::path::{Path, PathBuf};
+
+pub fn run(kakeibo: &Path, encoding: Option<Encoding>, args: FmtArgs) -> anyhow::Result<()> {
+    let files: Vec<PathBuf> = Processor::note_files(vec![kakeibo.to_path_buf()], encoding)?;
+
+    let mut unformatted: usize = 0;
+    for file in &files {
+        let bytes: Vec<u8> = std::fs::read(file)?;
+        let (src, detected) = encoding::detect(&bytes, encoding)
+            .with_context(|| format!("failed to read {}", file.display()))?;
+        let formatted: String = kakei_parser::format::format(&src)
+            .with_context(|| format!("failed to format {}", file.display()))?;
+        if formatted == src {
+            continue;
+        }
+
+        unformatted += 1;
+        if args.check {
+            println!("{}", file.display());
+        } else {
+            // Written back in the encoding it was read in, and with its BOM if it had one
+            let bom: &[u8] = if bytes.starts_with(b"\xEF\xBB\xBF") {
+                b"\xEF\xBB\xBF"
+            } else {
+                b""
+            };
+            let encoded: Vec<u8> = detected
+                .encode(&formatted)
+                .with_context(|| format!("{} cannot be written in {}", file.display(), detected))?;
+            std::fs::write(file, [bom, &encoded].concat())?;
+            eprintln!("Formatted {}", file.display());
+        }
+    }
+
+    if args.check && unformatted > 0 {
+        bail!("{} of {} files are not formatted", unformatted, files.len());
+    }
+    Ok(())
+}
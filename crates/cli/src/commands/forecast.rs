@@ -0,0 +1,23 @@
+use crate::cli::ForecastArgs;
+use kakei_processor::Processor;
+use kakei_types::date::Date;
+use std::io::IsTerminal;
+
+pub fn run(processor: &Processor, args: ForecastArgs) -> anyhow::Result<()> {
+    let from: Date = args.from.unwrap_or_else(Date::today);
+    let forecast = processor.forecast(from, args.until)?;
+
+    // Escape codes would garble the table piped to a file or another command
+    let color: bool = std::io::stdout().is_terminal();
+    println!("{}", forecast.table(color));
+    for (account, month) in forecast.first_negative() {
+        println!(
+            "{} goes negative in {:04}-{:02}",
+            account,
+            month.year(),
+            month.month()
+        );
+    }
+
+    Ok(())
+}
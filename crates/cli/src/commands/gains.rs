@@ -0,0 +1,23 @@
+use crate::cli::GainsArgs;
+use kakei_processor::{Processor, gains::CapitalGains};
+use kakei_types::date::Date;
+
+pub fn run(processor: &Processor, args: GainsArgs) -> anyhow::Result<()> {
+    let year: i32 = args.year.unwrap_or_else(|| Date::today().year());
+    let gains: CapitalGains = processor.capital_gains(args.currency, args.method, year)?;
+
+    println!("{}", gains.table());
+    println!("Method: {}", gains.method);
+    println!("Realized gain in {}: {}", gains.year, gains.realized);
+    println!("Held at the end of {}: {}", gains.year, gains.held);
+    println!("Cost basis: {}", gains.cost_basis);
+    match (gains.market_value, gains.unrealized()) {
+        (Some(value), Some(unrealized)) => {
+            println!("Market value: {}", value);
+            println!("Unrealized gain: {}", unrealized);
+        }
+        _ => println!("Unrealized gain: no price of {} in JPY", args.currency),
+    }
+
+    Ok(())
+}
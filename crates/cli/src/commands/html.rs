@@ -0,0 +1,26 @@
+use crate::cli::HtmlArgs;
+use anyhow::Context;
+use kakei_processor::{Processor, html::Page};
+use std::path::PathBuf;
+
+pub fn run(processor: &Processor, args: HtmlArgs) -> anyhow::Result<()> {
+    let pages: Vec<Page> = processor.site();
+
+    for page in &pages {
+        let path: PathBuf = args.out.join(&page.path);
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)
+                .with_context(|| format!("failed to create {}", directory.display()))?;
+        }
+        std::fs::write(&path, &page.html)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+
+    println!(
+        "Wrote {} pages to {}, open {}",
+        pages.len(),
+        args.out.display(),
+        args.out.join("index.html").display()
+    );
+    Ok(())
+}
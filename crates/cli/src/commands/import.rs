@@ -0,0 +1,311 @@
+use crate::cli::{
+    ImportAppArgs, ImportArgs, ImportCommand, ImportCsvArgs, ImportLedgerArgs, ImportOfxArgs,
+};
+use anyhow::Context;
+use inquire::Confirm;
+use kakei_processor::{
+    Processor,
+    apps::{self, AppStatements, Mapping},
+    configuration::Configuration,
+    duplicate::Duplicate,
+    encoding::{self, Encoding},
+    import::{self, CsvProfile, Rules, Statement, UNCATEGORIZED_EXPENSES, UNCATEGORIZED_INCOME},
+    ledger::{self, Journal},
+    ofx,
+};
+use kakei_types::entry::Transaction;
+use std::path::{Path, PathBuf};
+
+pub fn run(
+    processor: &Processor,
+    kakeibo: &Path,
+    config: &Path,
+    configuration: &Configuration,
+    args: ImportArgs,
+) -> anyhow::Result<()> {
+    match args.command {
+        ImportCommand::Csv(csv_args) => csv(processor, kakeibo, config, configuration, csv_args),
+        ImportCommand::Ofx(ofx_args) => ofx(processor, kakeibo, configuration, ofx_args),
+        ImportCommand::Ledger(ledger_args) => journal(kakeibo, ledger_args),
+        ImportCommand::Zaim(app_args) => app(
+            processor,
+            kakeibo,
+            config,
+            configuration,
+            app_args,
+            apps::read_zaim,
+        ),
+        ImportCommand::Moneyforward(app_args) => app(
+            processor,
+            kakeibo,
+            config,
+            configuration,
+            app_args,
+            apps::read_moneyforward,
+        ),
+    }
+}
+
+fn csv(
+    processor: &Processor,
+    kakeibo: &Path,
+    config: &Path,
+    configuration: &Configuration,
+    args: ImportCsvArgs,
+) -> anyhow::Result<()> {
+    let Some(profile): Option<&CsvProfile> = configuration.import.profiles.get(&args.profile)
+    else {
+        let names: Vec<&str> = configuration
+            .import
+            .profiles
+            .keys()
+            .map(String::as_str)
+            .collect();
+        anyhow::bail!(
+            "no profile \"{}\" under [import.profiles] in {}, known profiles: {}",
+            args.profile,
+            config.display(),
+            if names.is_empty() {
+                "none".to_string()
+            } else {
+                names.join(", ")
+            }
+        );
+    };
+    let rules: Rules = rules(args.rules.as_ref(), configuration)?;
+
+    let transactions: Vec<Transaction> = import::read_statement(
+        &read(&args.file, args.encoding.or(profile.encoding))?,
+        profile,
+        &rules,
+    )
+    .with_context(|| args.file.display().to_string())?;
+    let transactions: Vec<Transaction> =
+        skip_duplicates(processor, transactions, &profile.account, args.review)?;
+    write(kakeibo, &transactions, args.append)
+}
+
+fn ofx(
+    processor: &Processor,
+    kakeibo: &Path,
+    configuration: &Configuration,
+    args: ImportOfxArgs,
+) -> anyhow::Result<()> {
+    let rules: Rules = rules(args.rules.as_ref(), configuration)?;
+    let statements: Vec<Statement> = ofx::read_ofx(
+        &read(&args.file, args.encoding)?,
+        &configuration.import.accounts,
+        &rules,
+    )
+    .with_context(|| args.file.display().to_string())?;
+
+    let mut transactions: Vec<Transaction> = Vec::new();
+    for statement in statements {
+        transactions.extend(skip_duplicates(
+            processor,
+            statement.transactions,
+            &statement.account,
+            args.review,
+        )?);
+    }
+    write(kakeibo, &transactions, args.append)
+}
+
+fn app(
+    processor: &Processor,
+    kakeibo: &Path,
+    config: &Path,
+    configuration: &Configuration,
+    args: ImportAppArgs,
+    read_app: fn(&str, &Mapping, bool) -> Result<AppStatements, import::ImportError>,
+) -> anyhow::Result<()> {
+    let Some(path) = args
+        .mapping
+        .as_ref()
+        .or(configuration.import.mapping.as_ref())
+    else {
+        anyhow::bail!(
+            "no mapping of the categories and accounts of the app, pass --mapping or set mapping under [import] in {}",
+            config.display()
+        );
+    };
+    let mapping: Mapping =
+        Mapping::parse(&read(path, None)?).with_context(|| path.display().to_string())?;
+    let statements: AppStatements = read_app(&read(&args.file, args.encoding)?, &mapping, args.all)
+        .with_context(|| args.file.display().to_string())?;
+
+    let mut transactions: Vec<Transaction> = Vec::new();
+    for statement in statements.statements {
+        transactions.extend(skip_duplicates(
+            processor,
+            statement.transactions,
+            &statement.account,
+            args.review,
+        )?);
+    }
+    if statements.skipped > 0 {
+        eprintln!(
+            "Skipped {} records left out of the totals of the app or adjusting a balance",
+            statements.skipped
+        );
+    }
+    write(kakeibo, &transactions, args.append)
+}
+
+fn journal(kakeibo: &Path, args: ImportLedgerArgs) -> anyhow::Result<()> {
+    let journal: Journal = ledger::read_journal(&read(&args.file, args.encoding)?)
+        .with_context(|| args.file.display().to_string())?;
+
+    // The accounts and the prices each form one block
+    let mut entries: Vec<String> = Vec::new();
+    if !journal.opens.is_empty() {
+        entries.push(journal.opens.iter().map(|o| format!("{o}\n")).collect());
+    }
+    if !journal.prices.is_empty() {
+        entries.push(journal.prices.iter().map(|p| format!("{p}\n")).collect());
+    }
+    entries.extend(journal.transactions.iter().map(ToString::to_string));
+    let text: String = entries.join("\n");
+    // Journals balance by rules kakei does not follow, so every transaction is checked here
+    Processor::from_entries(kakei_parser::parse(&text)?)
+        .with_context(|| args.file.display().to_string())?;
+
+    if args.append {
+        super::append(kakeibo, &entries)?;
+        eprintln!(
+            "Wrote {} transactions, {} prices and {} accounts into {}",
+            journal.transactions.len(),
+            journal.prices.len(),
+            journal.opens.len(),
+            kakeibo.display()
+        );
+    } else {
+        print!("{text}");
+    }
+    Ok(())
+}
+
+/// Prints `transactions`, or appends them to the note.
+fn write(kakeibo: &Path, transactions: &[Transaction], append: bool) -> anyhow::Result<()> {
+    let text: String = transactions
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n");
+    // A payee the note cannot hold would otherwise break the note on the next read
+    kakei_parser::parse(&text)?;
+
+    let uncategorized: usize = transactions
+        .iter()
+        .filter(|t| {
+            t.postings
+                .iter()
+                .any(|p| p.account == UNCATEGORIZED_EXPENSES || p.account == UNCATEGORIZED_INCOME)
+        })
+        .count();
+
+    if append {
+        super::append(kakeibo, transactions)?;
+        eprintln!(
+            "Wrote {} transactions into {}",
+            transactions.len(),
+            kakeibo.display()
+        );
+    } else {
+        print!("{text}");
+    }
+    if uncategorized > 0 {
+        eprintln!(
+            "{uncategorized} of {} transactions matched no rule and went to {UNCATEGORIZED_EXPENSES} or {UNCATEGORIZED_INCOME}",
+            transactions.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Payee rules from `path`, or from the file in the configuration
+fn rules(path: Option<&PathBuf>, configuration: &Configuration) -> anyhow::Result<Rules> {
+    match path.or(configuration.import.rules.as_ref()) {
+        Some(path) => {
+            Ok(Rules::parse(&read(path, None)?).with_context(|| path.display().to_string())?)
+        }
+        None => Ok(Rules::default()),
+    }
+}
+
+/// `transactions` without the certain duplicates of those in the note, or those of them the user
+/// chooses to import when reviewing. Possible duplicates are imported unless the user declines them
+/// when reviewing, and listed when not.
+fn skip_duplicates(
+    processor: &Processor,
+    transactions: Vec<Transaction>,
+    account: &str,
+    review: bool,
+) -> anyhow::Result<Vec<Transaction>> {
+    let duplicates: Vec<Option<Duplicate>> = processor.duplicates(&transactions, account);
+
+    let mut kept: Vec<Transaction> = Vec::new();
+    let mut skipped: usize = 0;
+    let mut possible: Vec<String> = Vec::new();
+    for (transaction, duplicate) in transactions.into_iter().zip(duplicates) {
+        let (existing, certain) = match duplicate {
+            None => {
+                kept.push(transaction);
+                continue;
+            }
+            Some(Duplicate::Certain(existing)) => (existing, true),
+            Some(Duplicate::Possible(existing)) => (existing, false),
+        };
+
+        let import = if review {
+            eprint!("\n{transaction}");
+            let message = if certain {
+                format!(
+                    "This looks like {} \"{}\" in the note. Import it anyway?",
+                    existing.date, existing.payee
+                )
+            } else {
+                format!(
+                    "This may be {} \"{}\" in the note. Import it?",
+                    existing.date, existing.payee
+                )
+            };
+            Confirm::new(&message).with_default(!certain).prompt()?
+        } else {
+            if !certain {
+                possible.push(format!(
+                    "  {} \"{}\", like {} \"{}\"",
+                    transaction.date, transaction.payee, existing.date, existing.payee
+                ));
+            }
+            !certain
+        };
+        if import {
+            kept.push(transaction);
+        } else {
+            skipped += 1;
+        }
+    }
+
+    if skipped > 0 {
+        eprintln!("Skipped {skipped} probable duplicates of transactions in the note");
+    }
+    if !possible.is_empty() {
+        eprintln!(
+            "Imported {} possible duplicates of transactions in the note, check them or use --review:",
+            possible.len()
+        );
+        for line in possible {
+            eprintln!("{line}");
+        }
+    }
+    Ok(kept)
+}
+
+/// Reads the file at `path` as `encoding`, or as the encoding detected from its bytes.
+fn read(path: &PathBuf, encoding: Option<Encoding>) -> anyhow::Result<String> {
+    let bytes: Vec<u8> =
+        std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    encoding::decode(&bytes, encoding).with_context(|| format!("failed to read {}", path.display()))
+}
@@ -0,0 +1,675 @@
+use anyhow::Context;
+use kakei_parser::{
+    ParseError,
+    cst::{SyntaxTree, TokenKind},
+};
+use kakei_processor::{
+    Processor, ProcessorError,
+    encoding::{self, Encoding},
+};
+use kakei_types::entry::Entry;
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, Diagnostic,
+    DiagnosticSeverity, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, DocumentFormattingParams, GotoDefinitionParams,
+    GotoDefinitionResponse, Hover, HoverContents, HoverParams, HoverProviderCapability, Location,
+    MarkupContent, MarkupKind, OneOf, Position, PublishDiagnosticsParams, Range,
+    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Url,
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{Completion, Formatting, GotoDefinition, HoverRequest, Request as _},
+};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// Speaks the Language Server Protocol over stdio until the editor shuts it down.
+pub fn run(kakeibo: &Path, encoding: Option<Encoding>) -> anyhow::Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![":".to_string(), "\"".to_string()]),
+            ..Default::default()
+        }),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+
+    let mut server = Server::new(kakeibo, encoding);
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    break;
+                }
+                let response = server.request(request);
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                for diagnostics in server.notification(notification)? {
+                    let notification =
+                        Notification::new(PublishDiagnostics::METHOD.to_string(), diagnostics);
+                    connection
+                        .sender
+                        .send(Message::Notification(notification))?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+
+    drop(connection);
+    io_threads
+        .join()
+        .context("failed to stop the language server")
+}
+
+struct Server {
+    /// The note whose included files make up the ledger
+    kakeibo: PathBuf,
+
+    /// Encoding of the files on disk, detected from their bytes if `None`
+    encoding: Option<Encoding>,
+
+    /// Text of the documents open in the editor, which may not be saved yet, ordered so the
+    /// ledger is processed the same way every time
+    documents: BTreeMap<Url, String>,
+
+    /// Every file of the ledger with its text as last processed, the open documents as edited and
+    /// the others as read from disk, then the open documents which are not included in it
+    files: Vec<(PathBuf, String)>,
+
+    /// The ledger as last processed without an error, kept while the edits do not process
+    processor: Option<Processor>,
+
+    /// The error of the last processing, if any
+    error: Option<ProcessorError>,
+}
+
+impl Server {
+    fn new(kakeibo: &Path, encoding: Option<Encoding>) -> Self {
+        let mut server = Self {
+            kakeibo: std::fs::canonicalize(kakeibo).unwrap_or_else(|_| kakeibo.to_path_buf()),
+            encoding,
+            documents: BTreeMap::new(),
+            files: Vec::new(),
+            processor: None,
+            error: None,
+        };
+        server.process();
+        server
+    }
+
+    fn request(&self, request: Request) -> Response {
+        let id: RequestId = request.id.clone();
+        let result = match request.method.as_str() {
+            Completion::METHOD => params(request).map(|p| to_value(self.completion(p))),
+            HoverRequest::METHOD => params(request).map(|p| to_value(self.hover(p))),
+            GotoDefinition::METHOD => params(request).map(|p| to_value(self.definition(p))),
+            Formatting::METHOD => params(request).map(|p| to_value(self.formatting(p))),
+            method => {
+                let message = format!("unsupported request: {method}");
+                return Response::new_err(
+                    id,
+                    lsp_server::ErrorCode::MethodNotFound as i32,
+                    message,
+                );
+            }
+        };
+
+        match result {
+            Ok(value) => Response::new_ok(id, value),
+            Err(message) => {
+                Response::new_err(id, lsp_server::ErrorCode::InvalidParams as i32, message)
+            }
+        }
+    }
+
+    /// Updates the documents, and returns the diagnostics to publish.
+    fn notification(
+        &mut self,
+        notification: Notification,
+    ) -> anyhow::Result<Vec<PublishDiagnosticsParams>> {
+        let uri: Url = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let p: DidOpenTextDocumentParams = serde_json::from_value(notification.params)?;
+                self.documents
+                    .insert(p.text_document.uri.clone(), p.text_document.text);
+                p.text_document.uri
+            }
+            DidChangeTextDocument::METHOD => {
+                let p: DidChangeTextDocumentParams = serde_json::from_value(notification.params)?;
+                // The whole text is sent with TextDocumentSyncKind::FULL
+                if let Some(change) = p.content_changes.into_iter().last() {
+                    self.documents
+                        .insert(p.text_document.uri.clone(), change.text);
+                }
+                p.text_document.uri
+            }
+            DidCloseTextDocument::METHOD => {
+                let p: DidCloseTextDocumentParams = serde_json::from_value(notification.params)?;
+                self.documents.remove(&p.text_document.uri);
+                self.process();
+                return Ok(vec![PublishDiagnosticsParams::new(
+                    p.text_document.uri,
+                    Vec::new(),
+                    None,
+                )]);
+            }
+            DidSaveTextDocument::METHOD => {
+                self.process();
+                // Another open document may include the saved one
+                let uris: Vec<Url> = self.documents.keys().cloned().collect();
+                return Ok(uris.into_iter().map(|uri| self.diagnostics(uri)).collect());
+            }
+            _ => return Ok(Vec::new()),
+        };
+
+        self.process();
+        Ok(vec![self.diagnostics(uri)])
+    }
+
+    fn diagnostics(&self, uri: Url) -> PublishDiagnosticsParams {
+        let text: &str = self.documents.get(&uri).map_or("", String::as_str);
+        let diagnostic = |range: Range, message: String| Diagnostic {
+            range,
+            severity: Some(DiagnosticSeverity::ERROR),
+            source: Some("kakei".to_string()),
+            message,
+            ..Default::default()
+        };
+
+        let diagnostics: Vec<Diagnostic> = match kakei_parser::parse(text) {
+            Err(ParseError::Syntax { span, message, .. }) => {
+                let range = Range::new(position(text, span.start), position(text, span.end));
+                vec![diagnostic(range, message)]
+            }
+            Ok(_) => match &self.error {
+                None => Vec::new(),
+                Some(error) => match locate(text, error) {
+                    Some(range) => vec![diagnostic(range, error.to_string())],
+                    // The document with the entry shows it
+                    None if self.documents.values().any(|t| locate(t, error).is_some()) => {
+                        Vec::new()
+                    }
+                    // At line 0, for an error of no entry or of an entry in a closed file
+                    None => vec![diagnostic(Range::default(), error.to_string())],
+                },
+            },
+        };
+
+        PublishDiagnosticsParams::new(uri, diagnostics, None)
+    }
+
+    fn completion(&self, p: CompletionParams) -> Vec<CompletionItem> {
+        let location = p.text_document_position;
+        let Some(text) = self.documents.get(&location.text_document.uri) else {
+            return Vec::new();
+        };
+        let Some(processor) = &self.processor else {
+            return Vec::new();
+        };
+
+        // Inside the quotes of a payee, or anywhere an account is written
+        let line_start = offset(text, Position::new(location.position.line, 0));
+        let before = &text[line_start..offset(text, location.position)];
+        let (candidates, kind) = if before.matches('"').count() % 2 == 1 {
+            (processor.payees(), CompletionItemKind::TEXT)
+        } else {
+            (processor.accounts(), CompletionItemKind::FIELD)
+        };
+
+        candidates
+            .into_iter()
+            .map(|label| CompletionItem {
+                label: label.to_string(),
+                kind: Some(kind),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// The running balance of the accounts of the transaction, or of the posting, under the cursor
+    fn hover(&self, p: HoverParams) -> Option<Hover> {
+        let location = p.text_document_position_params;
+        let text = self.documents.get(&location.text_document.uri)?;
+        let tree = SyntaxTree::parse(text).ok()?;
+        let cursor = offset(text, location.position);
+
+        let index = tree.node_at(cursor)?;
+        let node = &tree.nodes[index];
+        let Some(Entry::Transaction(transaction)) = &node.entry else {
+            return None;
+        };
+
+        let lines = node.lines();
+        let relative = cursor - tree.offsets()[index];
+        let line = lines.iter().rposition(|line| line.offset <= relative)?;
+        let accounts: Vec<&str> = if line == 0 {
+            transaction
+                .postings
+                .iter()
+                .map(|p| p.account.as_str())
+                .collect()
+        } else if lines[line].is_trivia() {
+            return None;
+        } else {
+            let posting = lines[1..=line].iter().filter(|l| !l.is_trivia()).count() - 1;
+            vec![transaction.postings.get(posting)?.account.as_str()]
+        };
+
+        // The same transaction among the processed ones, which are sorted by date
+        let occurrence: usize = self.occurrence(&location.text_document.uri, &tree, index);
+        let processor = self.processor.as_ref()?;
+        let (position, _) = processor
+            .transactions()
+            .enumerate()
+            .filter(|(_, t)| t.date == transaction.date && t.payee == transaction.payee)
+            .nth(occurrence)?;
+
+        let mut value = String::new();
+        for account in accounts {
+            let balances: Vec<String> = processor
+                .running_balance(account, position)
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            value.push_str(&format!("`{}` {}\n\n", account, balances.join(", ")));
+        }
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: None,
+        })
+    }
+
+    /// How many transactions of the same date and payee as the `index`th node of the document
+    /// come before it in the ledger, whose files are processed in the order of `files`
+    fn occurrence(&self, uri: &Url, tree: &SyntaxTree, index: usize) -> usize {
+        let Some(Entry::Transaction(transaction)) = &tree.nodes[index].entry else {
+            return 0;
+        };
+        let same = |entry: &Entry| {
+            matches!(entry, Entry::Transaction(t)
+                if t.date == transaction.date && t.payee == transaction.payee)
+        };
+        let count = |tree: &SyntaxTree, end: usize| {
+            tree.nodes[..end]
+                .iter()
+                .filter(|node| node.entry.as_ref().is_some_and(same))
+                .count()
+        };
+
+        let path: Option<PathBuf> = uri.to_file_path().ok();
+        let before: usize = self
+            .files
+            .iter()
+            .take_while(|(file, _)| Some(file) != path.as_ref())
+            .filter_map(|(_, text)| SyntaxTree::parse(text).ok())
+            .map(|tree| count(&tree, tree.nodes.len()))
+            .sum();
+
+        before + count(tree, index)
+    }
+
+    /// The `open` directive of the account under the cursor
+    fn definition(&self, p: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let location = p.text_document_position_params;
+        let text = self.documents.get(&location.text_document.uri)?;
+        let tree = SyntaxTree::parse(text).ok()?;
+        let cursor = offset(text, location.position);
+
+        let index = tree.node_at(cursor)?;
+        let node = &tree.nodes[index];
+        let token = node.token_at(cursor - tree.offsets()[index])?;
+        if token.kind != TokenKind::Account {
+            return None;
+        }
+        let account = &node.text[token.span.clone()];
+
+        let mut files: Vec<(&Path, &str)> = self
+            .files
+            .iter()
+            .map(|(file, text)| (file.as_path(), text.as_str()))
+            .collect();
+        let path = location.text_document.uri.to_file_path();
+        if let Ok(path) = &path {
+            files.insert(0, (path, text));
+        }
+        files.into_iter().find_map(|(file, text)| {
+            let tree = SyntaxTree::parse(text).ok()?;
+            let index = tree.nodes.iter().position(
+                |node| matches!(&node.entry, Some(Entry::Open(open)) if open.account == account),
+            )?;
+
+            let start = tree.offsets()[index];
+            let end = start + tree.nodes[index].text.trim_end().len();
+            let range = Range::new(position(text, start), position(text, end));
+            let uri = Url::from_file_path(file).ok()?;
+            Some(GotoDefinitionResponse::Scalar(Location::new(uri, range)))
+        })
+    }
+
+    fn formatting(&self, p: DocumentFormattingParams) -> Option<Vec<TextEdit>> {
+        let text = self.documents.get(&p.text_document.uri)?;
+        let formatted = kakei_parser::format::format(text).ok()?;
+
+        let range = Range::new(Position::new(0, 0), position(text, text.len()));
+        Some(vec![TextEdit::new(range, formatted)])
+    }
+
+    /// The text of the document open at `path`
+    fn document(&self, path: &Path) -> Option<&String> {
+        self.documents
+            .iter()
+            .find(|(uri, _)| uri.to_file_path().is_ok_and(|p| p == path))
+            .map(|(_, text)| text)
+    }
+
+    /// Processes the whole ledger with the unsaved edits. Open documents which do not parse are
+    /// left out, and an error keeps the last processor.
+    fn process(&mut self) {
+        let mut entries: Vec<Entry> = Vec::new();
+        let mut files: Vec<(PathBuf, String)> = Vec::new();
+        let read = self.read(self.kakeibo.clone(), &mut entries, &mut files);
+        for (uri, text) in &self.documents {
+            if let Ok(path) = uri.to_file_path()
+                && !files.iter().any(|(file, _)| *file == path)
+            {
+                entries.extend(kakei_parser::parse(text).unwrap_or_default());
+                files.push((path, text.clone()));
+            }
+        }
+        self.files = files;
+
+        match Processor::from_entries(entries) {
+            Ok(processor) => {
+                self.processor = Some(processor);
+                self.error = read.err();
+            }
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    /// Reads the file at `path` and the files it includes, taking the text of the open documents
+    /// as edited and reading only the others from disk.
+    fn read(
+        &self,
+        path: PathBuf,
+        entries: &mut Vec<Entry>,
+        files: &mut Vec<(PathBuf, String)>,
+    ) -> Result<(), ProcessorError> {
+        let path: PathBuf = std::fs::canonicalize(&path).unwrap_or(path);
+        // An include cycle would otherwise never end
+        if files.iter().any(|(file, _)| *file == path) {
+            return Ok(());
+        }
+
+        let document: Option<&String> = self.document(&path);
+        let text: String = match document {
+            Some(text) => text.clone(),
+            None => {
+                let bytes = std::fs::read(&path).map_err(|source| ProcessorError::Io {
+                    path: path.clone(),
+                    source,
+                })?;
+                encoding::decode(&bytes, self.encoding).map_err(|source| {
+                    ProcessorError::Decode {
+                        path: path.clone(),
+                        source,
+                    }
+                })?
+            }
+        };
+        let parsed: Vec<Entry> = match kakei_parser::parse(&text) {
+            Ok(parsed) => parsed,
+            // The document shows its own syntax error
+            Err(_) if document.is_some() => Vec::new(),
+            Err(source) => return Err(ProcessorError::Parse { path, source }),
+        };
+        files.push((path.clone(), text));
+
+        for entry in parsed {
+            match entry {
+                Entry::Include(include) => {
+                    let base = path.parent().unwrap_or(Path::new(""));
+                    self.read(base.join(include.path), entries, files)?;
+                }
+                entry => entries.push(entry),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn params<P: serde::de::DeserializeOwned>(request: Request) -> Result<P, String> {
+    serde_json::from_value(request.params).map_err(|error| error.to_string())
+}
+
+fn to_value(value: impl serde::Serialize) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
+
+/// The first line of the entry a processor error is about
+fn locate(text: &str, error: &ProcessorError) -> Option<Range> {
+    let (date, payee) = match error {
+        ProcessorError::Unbalanced { date, payee, .. }
+        | ProcessorError::AmbiguousPosting { date, payee } => (*date, payee),
+        _ => return None,
+    };
+
+    let tree = SyntaxTree::parse(text).ok()?;
+    let index = tree.nodes.iter().position(|node| match &node.entry {
+        Some(Entry::Transaction(t)) => t.date == date && &t.payee == payee,
+        Some(Entry::Recurring(r)) => r.start == date && &r.payee == payee,
+        _ => false,
+    })?;
+
+    let start = tree.offsets()[index];
+    let end = start + tree.nodes[index].lines().first()?.text.len();
+    Some(Range::new(position(text, start), position(text, end)))
+}
+
+/// LSP position of a byte offset, counting UTF-16 code units in the line
+fn position(text: &str, offset: usize) -> Position {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count();
+    let column: usize = before
+        .rsplit('\n')
+        .next()
+        .unwrap_or("")
+        .chars()
+        .map(char::len_utf16)
+        .sum();
+
+    Position::new(line as u32, column as u32)
+}
+
+/// Byte offset of an LSP position, clamped to its line
+fn offset(text: &str, position: Position) -> usize {
+    let mut offset: usize = 0;
+    for (index, line) in text.split_inclusive('\n').enumerate() {
+        if index == position.line as usize {
+            let mut column: usize = 0;
+            for (byte, c) in line.char_indices() {
+                if column >= position.character as usize || c == '\n' || c == '\r' {
+                    return offset + byte;
+                }
+                column += c.len_utf16();
+            }
+            return offset + line.len();
+        }
+        offset += line.len();
+    }
+
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Server, offset, position};
+    use lsp_server::{ErrorCode, Request, RequestId};
+    use lsp_types::{
+        HoverContents, HoverParams, Position, Range, TextDocumentIdentifier,
+        TextDocumentPositionParams, Url,
+    };
+    use std::path::Path;
+
+    #[test]
+    fn utf16_positions() {
+        let text = "2026-03-14 \"松屋\"\n  Expenses:🍚  980 JPY\r\n";
+
+        // 松 and 屋 are one UTF-16 unit and three bytes, 🍚 is two units and four bytes
+        assert_eq!(position(text, 15), Position::new(0, 13));
+        assert_eq!(
+            position(text, text.find("  980").unwrap()),
+            Position::new(1, 13)
+        );
+        assert_eq!(position(text, text.len()), Position::new(2, 0));
+
+        assert_eq!(offset(text, Position::new(0, 13)), 15);
+        assert_eq!(
+            offset(text, Position::new(1, 13)),
+            text.find("  980").unwrap()
+        );
+        // Past the end of a line, before its line break
+        assert_eq!(offset(text, Position::new(1, 99)), text.find('\r').unwrap());
+        assert_eq!(offset(text, Position::new(9, 0)), text.len());
+    }
+
+    #[test]
+    fn unknown_method() {
+        let server = Server::new(Path::new("/nonexistent/note.kakei"), None);
+        let request = Request::new(RequestId::from(1), "kakei/unknown".to_string(), ());
+
+        let error = server.request(request).error.expect("an error response");
+        assert_eq!(error.code, ErrorCode::MethodNotFound as i32);
+    }
+
+    /// The hover text at the fifth character of `line`
+    fn hover(server: &Server, uri: Url, line: u32) -> String {
+        let params = HoverParams {
+            text_document_position_params: TextDocumentPositionParams::new(
+                TextDocumentIdentifier::new(uri),
+                Position::new(line, 4),
+            ),
+            work_done_progress_params: Default::default(),
+        };
+        match server.hover(params).map(|hover| hover.contents) {
+            Some(HoverContents::Markup(markup)) => markup.value,
+            v => panic!("unexpected hover: {v:?}"),
+        }
+    }
+
+    #[test]
+    fn hover_across_files() -> anyhow::Result<()> {
+        let mut server = Server::new(Path::new("/nonexistent/note.kakei"), None);
+        let transaction = "2026-03-14 \"Matsuya\"\n  Expenses:Food  980 JPY\n  Assets:Wallet\n";
+        let (a, b) = (
+            Url::parse("file:///nonexistent/a.kakei")?,
+            Url::parse("file:///nonexistent/b.kakei")?,
+        );
+        server.documents.insert(a, transaction.to_string());
+        server.documents.insert(b.clone(), transaction.to_string());
+        server.process();
+
+        // The transaction of b.kakei comes after the same one of a.kakei
+        assert_eq!(hover(&server, b, 1), "`Expenses:Food` 1960 JPY\n\n");
+
+        // An edit which does not balance keeps the last processor
+        let c = Url::parse("file:///nonexistent/c.kakei")?;
+        server.documents.insert(
+            c.clone(),
+            "2026-03-15 \"Sukiya\"\n  Expenses:Food  500 JPY\n  Assets:Wallet  -400 JPY\n"
+                .to_string(),
+        );
+        server.process();
+        assert!(server.error.is_some());
+        assert_eq!(
+            hover(&server, Url::parse("file:///nonexistent/a.kakei")?, 1),
+            "`Expenses:Food` 980 JPY\n\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn open_documents_of_the_ledger() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("kakei-lsp-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let dir = std::fs::canonicalize(&dir)?;
+        let (note, food, rent) = (
+            dir.join("note.kakei"),
+            dir.join("food.kakei"),
+            dir.join("rent.kakei"),
+        );
+        std::fs::write(&note, "include \"food.kakei\"\ninclude \"rent.kakei\"\n")?;
+        std::fs::write(
+            &food,
+            "2026-03-14 \"Matsuya\"\n  Expenses:Food  980 JPY\n  Assets:Wallet\n",
+        )?;
+        std::fs::write(
+            &rent,
+            "2026-03-25 \"Landlord\"\n  Expenses:Rent  85000 JPY\n  Assets:Bank\n",
+        )?;
+
+        let mut server = Server::new(&note, None);
+        let accounts = |server: &Server| -> Vec<String> {
+            let processor = server.processor.as_ref().expect("a processed ledger");
+            processor.accounts().into_iter().map(String::from).collect()
+        };
+        assert!(accounts(&server).contains(&"Expenses:Food".to_string()));
+
+        // The open document is read as edited, even once its file is gone
+        let uri = Url::from_file_path(&food).expect("an absolute path");
+        server.documents.insert(
+            uri.clone(),
+            "2026-03-14 \"Doutor\"\n  Expenses:Cafe  450 JPY\n  Assets:Wallet\n".to_string(),
+        );
+        std::fs::remove_file(&food)?;
+        server.process();
+        assert!(server.error.is_none());
+        assert_eq!(
+            accounts(&server),
+            vec![
+                "Assets:Bank",
+                "Assets:Wallet",
+                "Expenses:Cafe",
+                "Expenses:Rent"
+            ]
+        );
+
+        // An entry of a closed file, and a file which cannot be read, are shown at line 0
+        std::fs::write(
+            &rent,
+            "2026-03-25 \"Landlord\"\n  Expenses:Rent  85000 JPY\n  Assets:Bank  -8500 JPY\n",
+        )?;
+        server.process();
+        let diagnostics = server.diagnostics(uri.clone()).diagnostics;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range, Range::default());
+        assert!(diagnostics[0].message.contains("\"Landlord\""));
+
+        std::fs::remove_file(&rent)?;
+        server.process();
+        let diagnostics = server.diagnostics(uri).diagnostics;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range, Range::default());
+        assert!(diagnostics[0].message.contains("rent.kakei"));
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use crate::cli::{PricesArgs, PricesCommand, PricesImportArgs};
+use kakei_processor::{Processor, price::PriceDatabase};
+
+pub fn run(args: PricesArgs) -> anyhow::Result<()> {
+    match args.command {
+        PricesCommand::Import(import_args) => import(import_args),
+    }
+}
+
+fn import(args: PricesImportArgs) -> anyhow::Result<()> {
+    // Prices already in the output file are kept, and only they count as duplicates: a price
+    // written elsewhere in the note still belongs in the file
+    let mut canonical: PriceDatabase = match &args.output {
+        Some(output) if output.exists() => Processor::read(vec![output.clone()])?.prices().clone(),
+        _ => PriceDatabase::default(),
+    };
+    let mut imported: usize = 0;
+    let mut duplicates: usize = 0;
+
+    for file in &args.files {
+        let result = canonical.import_csv(file)?;
+        imported += result.imported.len();
+        duplicates += result.duplicates;
+    }
+
+    match &args.output {
+        Some(output) => {
+            std::fs::write(output, canonical.to_note())?;
+            eprintln!(
+                "Imported {} prices into {} ({} duplicates skipped)",
+                imported,
+                output.display(),
+                duplicates
+            );
+        }
+        None => print!("{}", canonical.to_note()),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use crate::cli::{RecurArgs, RecurCommand, RecurListArgs, RecurMaterializeArgs};
+use kakei_processor::Processor;
+use kakei_types::{date::Date, entry::Transaction};
+use std::path::Path;
+
+pub fn run(processor: &Processor, kakeibo: &Path, args: RecurArgs) -> anyhow::Result<()> {
+    match args.command {
+        RecurCommand::List(list_args) => list(processor, list_args),
+        RecurCommand::Materialize(materialize_args) => {
+            materialize(processor, kakeibo, materialize_args)
+        }
+    }
+}
+
+fn list(processor: &Processor, args: RecurListArgs) -> anyhow::Result<()> {
+    let until: Date = args.until.unwrap_or_else(Date::today);
+
+    for transaction in processor.pending(until)? {
+        println!("{}", transaction);
+    }
+
+    Ok(())
+}
+
+fn materialize(
+    processor: &Processor,
+    kakeibo: &Path,
+    args: RecurMaterializeArgs,
+) -> anyhow::Result<()> {
+    let until: Date = args.until.unwrap_or_else(Date::today);
+    let pending: Vec<Transaction> = processor.pending(until)?;
+
+    let text: String = pending.iter().map(|t| format!("\n{t}")).collect();
+    if args.dry_run {
+        print!("{}", text);
+        return Ok(());
+    }
+    if pending.is_empty() {
+        eprintln!("Nothing to materialize");
+        return Ok(());
+    }
+
+    super::append(kakeibo, &pending)?;
+
+    eprintln!(
+        "Wrote {} transactions into {}",
+        pending.len(),
+        kakeibo.display()
+    );
+    Ok(())
+}
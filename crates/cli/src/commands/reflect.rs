@@ -0,0 +1,37 @@
+use crate::cli::ReflectArgs;
+use kakei_processor::{Processor, configuration::Configuration, reflect::Reflection};
+use kakei_types::date::Date;
+
+pub fn run(
+    processor: &Processor,
+    configuration: &Configuration,
+    args: ReflectArgs,
+) -> anyhow::Result<()> {
+    let date: Date = args.month.unwrap_or_else(Date::today);
+    let reflection: Reflection = processor.reflection(date, configuration);
+
+    println!(
+        "Reflection for {:04}-{:02}",
+        reflection.month.year(),
+        reflection.month.month()
+    );
+    println!("{}", reflection.table());
+
+    match (reflection.goal, reflection.achieved()) {
+        (Some(goal), Some(achieved)) => println!(
+            "Savings goal: {}, saved {} ({})",
+            goal,
+            reflection.this_month.savings(),
+            achieved
+        ),
+        _ => println!("Savings goal: not set, add `savings_goal` to the configuration"),
+    }
+    if let Some(increase) = reflection.largest_increase() {
+        println!(
+            "How to improve: {} grew by {} from last month",
+            increase.name, increase.change
+        );
+    }
+
+    Ok(())
+}
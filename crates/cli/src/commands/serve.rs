@@ -0,0 +1,388 @@
+use crate::cli::ServeArgs;
+use crate::commands::watch::NoteWatcher;
+use kakei_processor::{Processor, encoding::Encoding};
+use kakei_types::{currency::Amount, date::Date};
+use serde_json::{Value, json};
+use std::{
+    collections::HashMap,
+    ops::Range,
+    path::Path,
+    sync::{Arc, PoisonError, RwLock},
+};
+use thiserror::Error;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const DASHBOARD: &str = include_str!("dashboard.html");
+
+/// The most months `/api/reports/monthly` totals at once, ten years
+const MAX_MONTHS: i32 = 120;
+
+/// The processor of the note, or why the note cannot be read
+type State = Arc<RwLock<Result<Processor, String>>>;
+
+pub fn run(kakeibo: &Path, encoding: Option<Encoding>, args: ServeArgs) -> anyhow::Result<()> {
+    let mut notes = NoteWatcher::new(kakeibo, encoding)?;
+    let state: State = Arc::new(RwLock::new(notes.read()?.map_err(|e| e.to_string())));
+    let server = Server::http(&args.bind)
+        .map_err(|error| anyhow::anyhow!("failed to listen on {}: {error}", args.bind))?;
+
+    let reloaded: State = Arc::clone(&state);
+    std::thread::spawn(move || {
+        // Errors of the watcher are only logged, so the note keeps being reloaded
+        loop {
+            if let Err(error) = notes.wait() {
+                eprintln!("error: {error:#}");
+            }
+            match notes.read() {
+                Ok(processor) => {
+                    let processor = processor.map_err(|e| e.to_string());
+                    if let Err(error) = &processor {
+                        eprintln!("error: {error}");
+                    }
+                    *reloaded.write().unwrap_or_else(PoisonError::into_inner) = processor;
+                }
+                Err(error) => eprintln!("error: {error:#}"),
+            }
+        }
+    });
+
+    println!(
+        "Serving {} on http://{}, press Ctrl-C to stop",
+        kakeibo.display(),
+        args.bind
+    );
+    for request in server.incoming_requests() {
+        // A client hanging up must not stop the server
+        if let Err(error) = respond(&state, request) {
+            eprintln!("error: {error}");
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+enum ApiError {
+    #[error("no such endpoint: {0}")]
+    NotFound(String),
+
+    #[error("only GET is supported")]
+    MethodNotAllowed,
+
+    #[error("{0}")]
+    BadRequest(String),
+
+    #[error("{0}")]
+    Unavailable(String),
+}
+
+impl ApiError {
+    fn status(&self) -> u16 {
+        match self {
+            Self::NotFound(_) => 404,
+            Self::MethodNotAllowed => 405,
+            Self::BadRequest(_) => 400,
+            Self::Unavailable(_) => 503,
+        }
+    }
+}
+
+fn respond(state: &State, request: Request) -> std::io::Result<()> {
+    let url: String = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+
+    if *request.method() == Method::Get && path == "/" {
+        return request.respond(with_type(
+            Response::from_string(DASHBOARD),
+            "text/html; charset=utf-8",
+        ));
+    }
+
+    let result: Result<Value, ApiError> = if *request.method() != Method::Get {
+        Err(ApiError::MethodNotAllowed)
+    } else {
+        match &*state.read().unwrap_or_else(PoisonError::into_inner) {
+            Ok(processor) => route(processor, path, &Params::parse(query)),
+            Err(error) => Err(ApiError::Unavailable(error.clone())),
+        }
+    };
+    let (status, body) = match result {
+        Ok(body) => (200, body),
+        Err(error) => (error.status(), json!({ "error": error.to_string() })),
+    };
+
+    request.respond(with_type(
+        Response::from_string(body.to_string()).with_status_code(status),
+        "application/json",
+    ))
+}
+
+fn with_type<R: std::io::Read>(response: Response<R>, content_type: &str) -> Response<R> {
+    match Header::from_bytes(&b"Content-Type"[..], content_type.as_bytes()) {
+        Ok(header) => response.with_header(header),
+        Err(()) => response,
+    }
+}
+
+fn route(processor: &Processor, path: &str, params: &Params) -> Result<Value, ApiError> {
+    let today: Date = Date::today();
+    let this_month: Date = Date::new(today.year(), today.month(), 1)
+        .map_err(|error| ApiError::BadRequest(error.to_string()))?;
+
+    match path {
+        "/api/balances" => {
+            let date: Date = params.date("date")?.unwrap_or(today);
+            let start: Date = processor.transactions().next().map_or(date, |t| t.date);
+            let accounts: Vec<Value> = processor
+                .account_tree(start..date.add_days(1))
+                .into_iter()
+                .map(|node| {
+                    json!({
+                        "account": node.name,
+                        "depth": node.depth,
+                        "totals": node.totals.iter().map(amount).collect::<Vec<_>>(),
+                    })
+                })
+                .collect();
+            Ok(json!({ "date": date.to_string(), "accounts": accounts }))
+        }
+        "/api/register" => {
+            let account: &str = params
+                .get("account")
+                .ok_or_else(|| ApiError::BadRequest("account is required".to_string()))?;
+            let rows: Vec<Value> = processor
+                .register(account, params.range(processor)?)
+                .into_iter()
+                .map(|row| {
+                    json!({
+                        "date": row.date.to_string(),
+                        "payee": row.payee,
+                        "account": row.account,
+                        "counterparts": row.counterparts,
+                        "amount": amount(&row.amount),
+                        "balance": amount(&row.balance),
+                    })
+                })
+                .collect();
+            Ok(json!({ "account": account, "rows": rows }))
+        }
+        "/api/reports/budget" => {
+            let date: Date = params.date("date")?.unwrap_or(today);
+            let budgets: Vec<Value> = processor
+                .budgets(date)
+                .into_iter()
+                .map(|budget| {
+                    json!({
+                        "account": budget.name,
+                        "from": budget.from.to_string(),
+                        "to": budget.to.to_string(),
+                        "budgeted": amount(&budget.budgeted),
+                        "actual": amount(&budget.actual),
+                        "remaining": amount(&budget.remaining),
+                        "used": budget.used.0,
+                        "over": budget.is_over(),
+                    })
+                })
+                .collect();
+            Ok(json!({ "date": date.to_string(), "budgets": budgets }))
+        }
+        "/api/reports/spending" => {
+            let month: Date = params.month("month")?.unwrap_or(this_month);
+            let categories: Vec<Value> = processor
+                .spending_by_category(month..month.add_months(1))
+                .into_iter()
+                .map(|(category, value)| json!({ "category": category, "jpy": value }))
+                .collect();
+            Ok(json!({ "month": month_label(month), "categories": categories }))
+        }
+        "/api/reports/monthly" => {
+            let month: Date = params.month("month")?.unwrap_or(this_month);
+            let count: i32 = params.number("months")?.unwrap_or(12).clamp(1, MAX_MONTHS);
+            let months: Vec<Value> = processor
+                .monthly_totals(month.add_months(1 - count)..month.add_months(1))
+                .into_iter()
+                .map(|total| {
+                    json!({
+                        "month": month_label(total.month),
+                        "income": total.income,
+                        "expenses": total.expenses,
+                    })
+                })
+                .collect();
+            Ok(json!({ "months": months }))
+        }
+        "/api/query" => {
+            let range: Range<Date> = params.range(processor)?;
+            let payee: String = params.get("payee").unwrap_or_default().to_lowercase();
+            let account: Option<&str> = params.get("account");
+            let transactions: Vec<Value> = processor
+                .transactions()
+                .filter(|t| range.contains(&t.date))
+                .filter(|t| t.payee.to_lowercase().contains(&payee))
+                .filter(|t| {
+                    account.is_none_or(|account| {
+                        t.postings.iter().any(|p| {
+                            p.account == account || p.account.starts_with(&format!("{account}:"))
+                        })
+                    })
+                })
+                .map(|t| {
+                    json!({
+                        "date": t.date.to_string(),
+                        "payee": t.payee,
+                        "postings": t.postings.iter().map(|p| json!({
+                            "account": p.account,
+                            "amount": p.amount.as_ref().map(amount),
+                        })).collect::<Vec<_>>(),
+                    })
+                })
+                .collect();
+            Ok(json!({ "transactions": transactions }))
+        }
+        path => Err(ApiError::NotFound(path.to_string())),
+    }
+}
+
+fn amount(amount: &Amount) -> Value {
+    json!({ "value": amount.value(), "currency": amount.code().to_string() })
+}
+
+fn month_label(month: Date) -> String {
+    format!("{:04}-{:02}", month.year(), month.month())
+}
+
+/// Parameters of the query string
+struct Params(HashMap<String, String>);
+
+impl Params {
+    fn parse(query: &str) -> Self {
+        Self(
+            query
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| {
+                    let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                    (decode(name), decode(value))
+                })
+                .collect(),
+        )
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    fn date(&self, name: &str) -> Result<Option<Date>, ApiError> {
+        self.get(name)
+            .map(|value| value.parse::<Date>())
+            .transpose()
+            .map_err(|error| ApiError::BadRequest(format!("{name}: {error}")))
+    }
+
+    /// `2026-04` as the first day of the month
+    fn month(&self, name: &str) -> Result<Option<Date>, ApiError> {
+        self.get(name)
+            .map(|value| format!("{value}-01").parse::<Date>())
+            .transpose()
+            .map_err(|error| ApiError::BadRequest(format!("{name}: {error}")))
+    }
+
+    fn number(&self, name: &str) -> Result<Option<i32>, ApiError> {
+        self.get(name)
+            .map(|value| value.parse::<i32>())
+            .transpose()
+            .map_err(|error| ApiError::BadRequest(format!("{name}: {error}")))
+    }
+
+    /// `from` and `until`, both included, defaulting to the first and the last transaction
+    fn range(&self, processor: &Processor) -> Result<Range<Date>, ApiError> {
+        let today: Date = Date::today();
+        let mut dates = processor.transactions().map(|t| t.date);
+        let first: Date = dates.next().unwrap_or(today);
+        let last: Date = dates.last().unwrap_or(first);
+
+        let from: Date = self.date("from")?.unwrap_or(first);
+        let until: Date = self.date("until")?.unwrap_or(last);
+        Ok(from..until.add_days(1))
+    }
+}
+
+/// Decodes `%XX` escapes and `+` of a query string.
+fn decode(text: &str) -> String {
+    let bytes: &[u8] = text.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut index: usize = 0;
+    while index < bytes.len() {
+        let escaped = text
+            .get(index + 1..index + 3)
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                index += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ApiError, MAX_MONTHS, Params, decode, route};
+    use kakei_processor::Processor;
+    use kakei_types::date::Date;
+
+    #[test]
+    fn decode_query() {
+        assert_eq!(decode("Assets%3ABank"), "Assets:Bank");
+        assert_eq!(decode("%E6%9D%BE%E5%B1%8B+%E6%B8%8B%E8%B0%B7"), "松屋 渋谷");
+        // Broken escapes are kept as they are
+        assert_eq!(decode("100%"), "100%");
+        assert_eq!(decode("%zz%4"), "%zz%4");
+    }
+
+    #[test]
+    fn query_params() -> anyhow::Result<()> {
+        let params = Params::parse("account=Expenses%3AFood&from=2026-03-01&months=3&flag&");
+
+        assert_eq!(params.get("account"), Some("Expenses:Food"));
+        assert_eq!(params.get("flag"), Some(""));
+        assert_eq!(params.date("from")?, Some(Date::new(2026, 3, 1)?));
+        assert_eq!(params.number("months")?, Some(3));
+        assert_eq!(params.date("until")?, None);
+
+        let params = Params::parse("month=2026-13&months=many");
+        assert!(matches!(
+            params.month("month"),
+            Err(ApiError::BadRequest(_))
+        ));
+        assert!(matches!(
+            params.number("months"),
+            Err(ApiError::BadRequest(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn clamp_months() -> anyhow::Result<()> {
+        let processor = Processor::from_entries(Vec::new())?;
+
+        for (query, count) in [("months=100000", MAX_MONTHS), ("months=-5", 1)] {
+            let body = route(&processor, "/api/reports/monthly", &Params::parse(query))?;
+            assert_eq!(
+                body["months"].as_array().map(Vec::len),
+                Some(count as usize)
+            );
+        }
+        Ok(())
+    }
+}
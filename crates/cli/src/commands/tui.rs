@@ -0,0 +1,510 @@
+use kakei_processor::{
+    Processor,
+    register::{AccountNode, RegisterRow},
+};
+use kakei_types::{
+    currency::Amount,
+    date::{Date, Period},
+};
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Cell, Paragraph, Row, Table, TableState},
+};
+use std::ops::Range;
+
+pub fn run(processor: &Processor) -> anyhow::Result<()> {
+    let mut terminal: DefaultTerminal = ratatui::init();
+    let result = App::new(processor).run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+/// How much of the ledger is shown at once
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Span {
+    Week,
+    Month,
+    Year,
+    All,
+}
+
+impl Span {
+    fn next(self) -> Self {
+        match self {
+            Self::Week => Self::Month,
+            Self::Month => Self::Year,
+            Self::Year => Self::All,
+            Self::All => Self::Week,
+        }
+    }
+
+    /// The period containing `date`, with weeks starting on Monday
+    fn containing(self, date: Date) -> Option<Range<Date>> {
+        // 10957 days after the epoch is 2000-01-01; the week anchor is the Monday 2000-01-03
+        let anchor = Date::from_days(10_957);
+        match self {
+            Self::Week => Some(Period::Weekly.containing(anchor.add_days(2), date)),
+            Self::Month => Some(Period::Monthly.containing(anchor, date)),
+            Self::Year => Some(Period::Yearly.containing(anchor, date)),
+            Self::All => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Focus {
+    Accounts,
+    Register,
+}
+
+struct App<'a> {
+    processor: &'a Processor,
+    span: Span,
+
+    /// A date in the period shown
+    date: Date,
+    accounts: Vec<AccountNode>,
+    account_state: TableState,
+
+    /// Register of the selected account before searching
+    rows: Vec<RegisterRow>,
+    register_state: TableState,
+    focus: Focus,
+    search: String,
+    searching: bool,
+    quit: bool,
+}
+
+impl<'a> App<'a> {
+    fn new(processor: &'a Processor) -> Self {
+        let mut app = Self {
+            processor,
+            span: Span::Month,
+            date: Date::today(),
+            accounts: Vec::new(),
+            account_state: TableState::default().with_selected(0),
+            rows: Vec::new(),
+            register_state: TableState::default(),
+            focus: Focus::Accounts,
+            search: String::new(),
+            searching: false,
+            quit: false,
+        };
+        app.refresh();
+        app
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> anyhow::Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle(key);
+            }
+        }
+        Ok(())
+    }
+
+    fn range(&self) -> Range<Date> {
+        self.span.containing(self.date).unwrap_or_else(|| {
+            let mut dates = self.processor.transactions().map(|t| t.date);
+            let first = dates.next().unwrap_or(self.date);
+            first..dates.last().unwrap_or(first).add_days(1)
+        })
+    }
+
+    fn label(&self) -> String {
+        let start = self.range().start;
+        match self.span {
+            Span::Week => format!("week of {start}"),
+            Span::Month => format!("{:04}-{:02}", start.year(), start.month()),
+            Span::Year => format!("{:04}", start.year()),
+            Span::All => "all time".to_string(),
+        }
+    }
+
+    fn selected_account(&self) -> Option<&AccountNode> {
+        self.account_state
+            .selected()
+            .and_then(|index| self.accounts.get(index))
+    }
+
+    /// Reads the account tree and the register again for the period shown.
+    fn refresh(&mut self) {
+        self.accounts = self.processor.account_tree(self.range());
+        let last = self.accounts.len().saturating_sub(1);
+        self.account_state
+            .select(self.account_state.selected().map(|index| index.min(last)));
+        self.load_register();
+    }
+
+    fn load_register(&mut self) {
+        self.rows = match self.selected_account() {
+            Some(account) => self.processor.register(&account.name, self.range()),
+            None => Vec::new(),
+        };
+        self.select_latest();
+    }
+
+    /// Rows of the register matching the search
+    fn visible_rows(&self) -> Vec<&RegisterRow> {
+        let search = self.search.to_lowercase();
+        self.rows
+            .iter()
+            .filter(|row| {
+                std::iter::once(&row.payee)
+                    .chain(std::iter::once(&row.account))
+                    .chain(&row.counterparts)
+                    .any(|text| text.to_lowercase().contains(&search))
+            })
+            .collect()
+    }
+
+    fn select_latest(&mut self) {
+        let count = self.visible_rows().len();
+        self.register_state.select(count.checked_sub(1));
+    }
+
+    fn handle(&mut self, key: KeyEvent) {
+        if self.searching {
+            match key.code {
+                KeyCode::Char(c) => self.search.push(c),
+                KeyCode::Backspace => {
+                    self.search.pop();
+                }
+                KeyCode::Enter => self.searching = false,
+                KeyCode::Esc => {
+                    self.search.clear();
+                    self.searching = false;
+                }
+                _ => return,
+            }
+            self.select_latest();
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Esc if !self.search.is_empty() => {
+                self.search.clear();
+                self.select_latest();
+            }
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Char('/') => {
+                self.searching = true;
+                self.focus = Focus::Register;
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    Focus::Accounts => Focus::Register,
+                    Focus::Register => Focus::Accounts,
+                };
+            }
+            KeyCode::Char('p') => {
+                self.span = self.span.next();
+                self.refresh();
+            }
+            KeyCode::Left | KeyCode::Char('h') if self.span != Span::All => {
+                self.date = self.range().start.add_days(-1);
+                self.refresh();
+            }
+            KeyCode::Right | KeyCode::Char('l') if self.span != Span::All => {
+                self.date = self.range().end;
+                self.refresh();
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.scroll(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll(1),
+            KeyCode::PageUp => self.scroll(-10),
+            KeyCode::PageDown => self.scroll(10),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll(isize::MIN),
+            KeyCode::End | KeyCode::Char('G') => self.scroll(isize::MAX),
+            _ => {}
+        }
+    }
+
+    /// Moves the selection of the focused pane by `by` rows.
+    fn scroll(&mut self, by: isize) {
+        let (state, count) = match self.focus {
+            Focus::Accounts => (&mut self.account_state, self.accounts.len()),
+            Focus::Register => {
+                let count = self.visible_rows().len();
+                (&mut self.register_state, count)
+            }
+        };
+        let Some(last) = count.checked_sub(1) else {
+            return;
+        };
+        let current = state.selected().unwrap_or_default() as isize;
+        state.select(Some(
+            current.saturating_add(by).clamp(0, last as isize) as usize
+        ));
+
+        if self.focus == Focus::Accounts {
+            self.load_register();
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [header, body, search] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .areas(frame.area());
+        let [accounts, register] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(body);
+
+        let help = format!(
+            " ◀ {} ▶   p: period  ←/→: move  tab: switch pane  /: search  q: quit",
+            self.label()
+        );
+        frame.render_widget(
+            Line::from(help).style(Style::new().add_modifier(Modifier::BOLD)),
+            header,
+        );
+        self.draw_accounts(frame, accounts);
+        self.draw_register(frame, register);
+        self.draw_search(frame, search);
+    }
+
+    fn draw_accounts(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = self.accounts.iter().map(|node| {
+            let name = node.name.rsplit(':').next().unwrap_or(&node.name);
+            Row::new([
+                Cell::from(format!("{}{name}", "  ".repeat(node.depth))),
+                amounts(&node.totals),
+            ])
+        });
+        let table = Table::new(rows, [Constraint::Fill(1), Constraint::Length(16)])
+            .block(pane("Accounts", self.focus == Focus::Accounts))
+            .row_highlight_style(highlight(self.focus == Focus::Accounts));
+
+        frame.render_stateful_widget(table, area, &mut self.account_state);
+    }
+
+    fn draw_register(&mut self, frame: &mut Frame, area: Rect) {
+        let account = self.selected_account().map(|a| a.name.clone());
+        let rows: Vec<Row> = self
+            .visible_rows()
+            .into_iter()
+            .map(|row| {
+                // The other side of the transaction, unless it was posted to a sub-account
+                let other = if Some(&row.account) == account.as_ref() {
+                    row.counterparts.join(", ")
+                } else {
+                    row.account.clone()
+                };
+                Row::new([
+                    Cell::from(row.date.to_string()),
+                    Cell::from(row.payee.clone()),
+                    Cell::from(other),
+                    amounts(&[row.amount]),
+                    amounts(&[row.balance]),
+                ])
+            })
+            .collect();
+
+        let title = format!("{} ({} postings)", account.unwrap_or_default(), rows.len());
+        let header = Row::new(["Date", "Payee", "Account", "Amount", "Balance"])
+            .style(Style::new().add_modifier(Modifier::BOLD));
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(10),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Length(14),
+                Constraint::Length(14),
+            ],
+        )
+        .header(header)
+        .block(pane(&title, self.focus == Focus::Register))
+        .row_highlight_style(highlight(self.focus == Focus::Register));
+
+        frame.render_stateful_widget(table, area, &mut self.register_state);
+    }
+
+    fn draw_search(&self, frame: &mut Frame, area: Rect) {
+        let text = if self.searching || !self.search.is_empty() {
+            format!("/{}", self.search)
+        } else {
+            "/ to search payees and accounts".to_string()
+        };
+        let style = if self.searching || !self.search.is_empty() {
+            Style::new()
+        } else {
+            Style::new().fg(Color::DarkGray)
+        };
+        frame.render_widget(
+            Paragraph::new(text)
+                .style(style)
+                .block(pane("Search", self.searching)),
+            area,
+        );
+
+        if self.searching {
+            let column = 2 + self.search.chars().count() as u16;
+            frame.set_cursor_position(Position::new(area.x + column, area.y + 1));
+        }
+    }
+}
+
+fn pane(title: &str, focused: bool) -> Block<'_> {
+    let style = if focused {
+        Style::new().fg(Color::Cyan)
+    } else {
+        Style::new()
+    };
+    Block::bordered().title(title).border_style(style)
+}
+
+fn highlight(focused: bool) -> Style {
+    if focused {
+        Style::new().add_modifier(Modifier::REVERSED)
+    } else {
+        Style::new().add_modifier(Modifier::BOLD)
+    }
+}
+
+/// Right-aligned amounts, negative ones in red
+fn amounts(amounts: &[Amount]) -> Cell<'static> {
+    let text: Vec<String> = amounts.iter().map(Amount::to_string).collect();
+    let style = if amounts.iter().any(|a| a.value() < 0) {
+        Style::new().fg(Color::Red)
+    } else {
+        Style::new()
+    };
+    Cell::from(Line::from(text.join(", ")).right_aligned()).style(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{App, Focus, Span};
+    use kakei_processor::Processor;
+    use kakei_types::date::Date;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent};
+
+    const NOTE: &str = "\
+2026-03-30 \"Matsuya\"
+  Expenses:Food  780 JPY
+  Assets:Wallet
+2026-04-01 \"Doutor\"
+  Expenses:Cafe  450 JPY
+  Assets:Wallet
+2026-04-05 \"Matsuya\"
+  Expenses:Food  980 JPY
+  Assets:Wallet
+2026-04-10 \"Sukiya\"
+  Expenses:Food  500 JPY
+  Assets:Wallet
+";
+
+    fn press(app: &mut App, codes: &[KeyCode]) {
+        for code in codes {
+            app.handle(KeyEvent::from(*code));
+        }
+    }
+
+    fn payees(app: &App) -> Vec<String> {
+        app.visible_rows()
+            .into_iter()
+            .map(|row| row.payee.clone())
+            .collect()
+    }
+
+    #[test]
+    fn span_containing() -> anyhow::Result<()> {
+        let date = Date::new(2026, 4, 10)?;
+
+        assert_eq!(
+            Span::Week.containing(date),
+            Some(Date::new(2026, 4, 6)?..Date::new(2026, 4, 13)?)
+        );
+        assert_eq!(
+            Span::Month.containing(date),
+            Some(Date::new(2026, 4, 1)?..Date::new(2026, 5, 1)?)
+        );
+        assert_eq!(
+            Span::Year.containing(date),
+            Some(Date::new(2026, 1, 1)?..Date::new(2027, 1, 1)?)
+        );
+        assert_eq!(Span::All.containing(date), None);
+        assert_eq!(Span::All.next(), Span::Week);
+        Ok(())
+    }
+
+    #[test]
+    fn keys() -> anyhow::Result<()> {
+        let processor = Processor::from_entries(kakei_parser::parse(NOTE)?)?;
+        let mut app = App::new(&processor);
+        app.date = Date::new(2026, 4, 10)?;
+        app.refresh();
+
+        let names: Vec<&str> = app.accounts.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Assets",
+                "Assets:Wallet",
+                "Expenses",
+                "Expenses:Cafe",
+                "Expenses:Food"
+            ]
+        );
+        assert_eq!(app.visible_rows().len(), 3);
+        assert_eq!(app.register_state.selected(), Some(2));
+
+        // The selection stops at either end and the register follows the account
+        press(&mut app, &[KeyCode::Char('j'), KeyCode::End, KeyCode::Down]);
+        assert_eq!(app.account_state.selected(), Some(4));
+        assert_eq!(payees(&app), vec!["Matsuya", "Sukiya"]);
+
+        press(&mut app, &[KeyCode::Tab, KeyCode::Home]);
+        assert_eq!(app.focus, Focus::Register);
+        assert_eq!(app.register_state.selected(), Some(0));
+        press(&mut app, &[KeyCode::PageDown]);
+        assert_eq!(app.register_state.selected(), Some(1));
+        assert_eq!(app.account_state.selected(), Some(4));
+
+        press(
+            &mut app,
+            &[
+                KeyCode::Char('/'),
+                KeyCode::Char('s'),
+                KeyCode::Char('u'),
+                KeyCode::Char('k'),
+                KeyCode::Char('x'),
+                KeyCode::Backspace,
+                KeyCode::Enter,
+            ],
+        );
+        assert!(!app.searching);
+        assert_eq!(payees(&app), vec!["Sukiya"]);
+        assert_eq!(app.register_state.selected(), Some(0));
+        press(&mut app, &[KeyCode::Esc]);
+        assert_eq!(payees(&app), vec!["Matsuya", "Sukiya"]);
+        assert!(!app.quit);
+
+        press(&mut app, &[KeyCode::Char('h')]);
+        assert_eq!(app.label(), "2026-03");
+        assert_eq!(app.visible_rows().len(), 1);
+        press(&mut app, &[KeyCode::Right]);
+        assert_eq!(app.label(), "2026-04");
+
+        press(&mut app, &[KeyCode::Char('p'), KeyCode::Char('p')]);
+        assert_eq!(app.span, Span::All);
+        press(&mut app, &[KeyCode::Char('h')]);
+        assert_eq!(app.label(), "all time");
+        assert_eq!(app.visible_rows().len(), 3);
+
+        press(&mut app, &[KeyCode::Char('q')]);
+        assert!(app.quit);
+        Ok(())
+    }
+}
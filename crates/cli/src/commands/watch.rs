@@ -0,0 +1,125 @@
+use kakei_processor::{Processor, ProcessorError, cache::NoteCache, encoding::Encoding};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::{
+    collections::HashSet,
+    io::{IsTerminal, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{Receiver, channel},
+    time::Duration,
+};
+
+/// Clears the terminal and moves the cursor to the top left corner.
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+/// Draws `report` of the note, and again whenever the note or a file it includes changes.
+pub fn run(
+    kakeibo: &Path,
+    encoding: Option<Encoding>,
+    report: &dyn Fn(&Processor) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut notes = NoteWatcher::new(kakeibo, encoding)?;
+    // Piped into a file or a pager, each report follows the last instead
+    let terminal: bool = std::io::stdout().is_terminal();
+
+    loop {
+        if terminal {
+            print!("{CLEAR}");
+        }
+        match notes.read()? {
+            Ok(processor) => {
+                if let Err(error) = report(&processor) {
+                    println!("error: {error:#}");
+                }
+            }
+            Err(error) => println!("error: {error}"),
+        }
+
+        println!(
+            "\nWatching {} files, press Ctrl-C to stop",
+            notes.files.len()
+        );
+        std::io::stdout().flush()?;
+
+        notes.wait()?;
+    }
+}
+
+/// The note and the files it includes, read again after any of them changes
+pub(crate) struct NoteWatcher {
+    kakeibo: PathBuf,
+    cache: NoteCache,
+    watcher: RecommendedWatcher,
+    receiver: Receiver<notify::Result<Event>>,
+    directories: HashSet<PathBuf>,
+
+    /// Every file of the last read, as the watcher reports them
+    files: HashSet<PathBuf>,
+}
+
+impl NoteWatcher {
+    pub(crate) fn new(kakeibo: &Path, encoding: Option<Encoding>) -> anyhow::Result<Self> {
+        let (sender, receiver) = channel();
+        Ok(Self {
+            kakeibo: kakeibo.to_path_buf(),
+            cache: NoteCache::new(encoding),
+            watcher: notify::recommended_watcher(sender)?,
+            receiver,
+            directories: HashSet::new(),
+            files: HashSet::new(),
+        })
+    }
+
+    /// Reads the note, parsing only the files changed since the last read, and watches every
+    /// file read. Only failing to watch is an error, a broken note is returned as it is.
+    pub(crate) fn read(&mut self) -> anyhow::Result<Result<Processor, ProcessorError>> {
+        let mut files: Vec<PathBuf> = vec![self.kakeibo.clone()];
+        let processor = self.cache.read(vec![self.kakeibo.clone()]);
+        // Watch a missing include too, so creating it reads the note again
+        if let Err(ProcessorError::Io { path, .. }) = &processor {
+            files.push(path.clone());
+        }
+        files.extend(self.cache.files().map(Path::to_path_buf));
+
+        // Editors often save by renaming a new file over the old one, which ends a watch on the
+        // file itself, so the directories are watched instead
+        self.files = files.iter().filter_map(|file| absolute(file)).collect();
+        for directory in self.files.iter().filter_map(|file| file.parent()) {
+            if self.directories.insert(directory.to_path_buf()) {
+                self.watcher.watch(directory, RecursiveMode::NonRecursive)?;
+            }
+        }
+
+        Ok(processor)
+    }
+
+    /// Blocks until one of the files of the last read changes.
+    pub(crate) fn wait(&self) -> anyhow::Result<()> {
+        loop {
+            let event: Event = self.receiver.recv()??;
+            if !event.kind.is_access() && event.paths.iter().any(|path| self.files.contains(path)) {
+                break;
+            }
+        }
+
+        // A single save raises several events, which should be read once
+        while self
+            .receiver
+            .recv_timeout(Duration::from_millis(50))
+            .is_ok()
+        {}
+        Ok(())
+    }
+}
+
+/// `path` in the form the watcher reports it, which works for files not created yet
+fn absolute(path: &Path) -> Option<PathBuf> {
+    let directory: &Path = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    Some(
+        std::fs::canonicalize(directory)
+            .ok()?
+            .join(path.file_name()?),
+    )
+}
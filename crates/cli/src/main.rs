@@ -1,7 +1,64 @@
 use clap::Parser;
+use kakei::commands;
 use kakei::prelude::*;
+use kakei_processor::{Processor, configuration::Configuration};
 
 fn main() -> anyhow::Result<()> {
-    let _ = CLIArgs::parse();
-    todo!()
+    let args = CLIArgs::parse();
+    // Formatting only needs the syntax, so the note is processed by the commands needing it, and
+    // the configuration is only loaded, and created when missing, by those reading it
+    let configuration = || -> anyhow::Result<Configuration> { Ok(confy::load_path(&args.config)?) };
+    let read = || match args.encoding {
+        Some(encoding) => Processor::read_encoded(vec![args.kakeibo.clone()], encoding),
+        None => Processor::read(vec![args.kakeibo.clone()]),
+    };
+
+    // Reports are drawn once, or again whenever the note changes with `--watch`
+    let report = |watch: bool, report: &dyn Fn(&Processor) -> anyhow::Result<()>| {
+        if watch {
+            commands::watch::run(&args.kakeibo, args.encoding, report)
+        } else {
+            report(&read()?)
+        }
+    };
+
+    match args.command.unwrap_or_default() {
+        Command::Balance(balance_args) => report(balance_args.watch, &|processor| {
+            commands::balance::run(processor, balance_args.clone())
+        }),
+        Command::Prices(prices_args) => commands::prices::run(prices_args),
+        Command::Gains(gains_args) => report(gains_args.watch, &|processor| {
+            commands::gains::run(processor, gains_args.clone())
+        }),
+        Command::Budget(budget_args) => report(budget_args.watch, &|processor| {
+            commands::budget::run(processor, budget_args.clone())
+        }),
+        Command::Reflect(reflect_args) => {
+            let configuration: Configuration = configuration()?;
+            report(reflect_args.watch, &|processor| {
+                commands::reflect::run(processor, &configuration, reflect_args.clone())
+            })
+        }
+        Command::Recur(recur_args) => commands::recur::run(&read()?, &args.kakeibo, recur_args),
+        Command::Fmt(fmt_args) => commands::fmt::run(&args.kakeibo, args.encoding, fmt_args),
+        Command::Add(add_args) => commands::add::run(&read()?, &args.kakeibo, add_args),
+        Command::Lsp => commands::lsp::run(&args.kakeibo, args.encoding),
+        Command::Forecast(forecast_args) => report(forecast_args.watch, &|processor| {
+            commands::forecast::run(processor, forecast_args.clone())
+        }),
+        Command::Tui => commands::tui::run(&read()?),
+        Command::Chart(chart_args) => commands::chart::run(&read()?, chart_args),
+        Command::Html(html_args) => commands::html::run(&read()?, html_args),
+        Command::Serve(serve_args) => {
+            commands::serve::run(&args.kakeibo, args.encoding, serve_args)
+        }
+        Command::Import(import_args) => commands::import::run(
+            &read()?,
+            &args.kakeibo,
+            &args.config,
+            &configuration()?,
+            import_args,
+        ),
+        Command::Export(export_args) => commands::export::run(&read()?, export_args),
+    }
 }
@@ -0,0 +1,451 @@
+//! Lossless syntax tree of kakeibo notes
+//!
+//! Every byte of the source belongs to one [`Node`], whitespace and comments included, so a
+//! note printed back from its tree is exactly what was read. Tools can replace one entry and
+//! write the file without disturbing the rest.
+
+use crate::{ParseError, parse_tokens};
+use kakei_types::entry::Entry;
+use std::ops::Range;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SyntaxTree {
+    pub nodes: Vec<Node>,
+}
+
+/// One entry with its lines, or one blank or comment line between entries
+#[derive(Debug, PartialEq, Clone)]
+pub struct Node {
+    /// The source of the node, line endings included
+    pub text: String,
+
+    /// `None` for a blank or comment line
+    pub entry: Option<Entry>,
+
+    /// Tokens covering the whole text, with spans relative to it
+    pub tokens: Vec<Token>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TokenKind {
+    Whitespace,
+    Newline,
+
+    /// `; comment`, up to the end of the line
+    Comment,
+
+    /// `include`, `open`, `price`, `budget`, `from`, `until` and periods
+    Keyword,
+
+    /// `~`, `{` and `}`
+    Punctuation,
+    Date,
+    Account,
+
+    /// An amount or a rate, such as `-1,200` or `0.0145`
+    Number,
+    Currency,
+
+    /// A quoted payee or path, quotes included
+    String,
+}
+
+/// One line of a [`Node`]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Line<'a> {
+    /// The line without its line ending
+    pub text: &'a str,
+    pub tokens: &'a [Token],
+
+    /// Offset of the line in the node
+    pub offset: usize,
+}
+
+impl SyntaxTree {
+    pub fn parse(src: &str) -> Result<Self, ParseError> {
+        let (entries, tokens) = parse_tokens(src)?;
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut position: usize = 0;
+
+        for (entry, span) in entries {
+            nodes.extend(trivia(src, position..span.start, &tokens));
+            nodes.push(Node::new(src, span.clone(), Some(entry), &tokens));
+            position = span.end;
+        }
+        nodes.extend(trivia(src, position..src.len(), &tokens));
+
+        Ok(Self { nodes })
+    }
+
+    /// The entries in the order they are written
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.nodes.iter().filter_map(|node| node.entry.as_ref())
+    }
+
+    /// Byte offset of each node in the printed note
+    pub fn offsets(&self) -> Vec<usize> {
+        self.nodes
+            .iter()
+            .scan(0, |offset, node| {
+                let start = *offset;
+                *offset += node.text.len();
+                Some(start)
+            })
+            .collect()
+    }
+
+    /// Index of the node containing the byte `offset`
+    pub fn node_at(&self, offset: usize) -> Option<usize> {
+        let offsets = self.offsets();
+        (0..self.nodes.len())
+            .find(|&index| offset < offsets[index] + self.nodes[index].text.len())
+            .or_else(|| self.nodes.len().checked_sub(1))
+    }
+
+    /// Replaces the node at `index` with `text`, which must be exactly one entry, such as a
+    /// rewritten entry. The line ending of the node is added if `text` has none, so the next
+    /// node stays on its own line.
+    pub fn replace(&mut self, index: usize, text: &str) -> Result<(), ParseError> {
+        let text: String = if text.ends_with('\n') {
+            text.to_string()
+        } else if self.nodes[index].text.ends_with("\r\n") {
+            format!("{text}\r\n")
+        } else {
+            format!("{text}\n")
+        };
+        let mut replacement = Self::parse(&text)?;
+        let entries = replacement.entries().count();
+        if entries != 1 || replacement.nodes.len() != 1 {
+            return Err(ParseError::new(
+                &text,
+                0..text.len(),
+                format!(
+                    "expected exactly one entry without blank or comment lines, found {entries} entries in {} lines",
+                    replacement.nodes.len()
+                ),
+            ));
+        }
+
+        self.nodes[index] = replacement.nodes.remove(0);
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for SyntaxTree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for node in &self.nodes {
+            write!(f, "{}", node.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl Node {
+    /// The node of `span` in `src`, with the tokens the parser read in it
+    fn new(src: &str, span: Range<usize>, entry: Option<Entry>, tokens: &[Token]) -> Self {
+        let mut node: Vec<Token> = Vec::new();
+        let mut position: usize = span.start;
+        let first = tokens.partition_point(|token| token.span.start < span.start);
+        for token in tokens[first..]
+            .iter()
+            .take_while(|token| token.span.start < span.end)
+        {
+            node.extend(layout(src, position..token.span.start));
+            node.push(token.clone());
+            position = token.span.end;
+        }
+        node.extend(layout(src, position..span.end));
+
+        for token in &mut node {
+            token.span = token.span.start - span.start..token.span.end - span.start;
+        }
+        Self {
+            text: src[span].to_string(),
+            entry,
+            tokens: node,
+        }
+    }
+
+    pub fn lines(&self) -> Vec<Line<'_>> {
+        let mut lines: Vec<Line> = Vec::new();
+        let mut first: usize = 0;
+        let mut offset: usize = 0;
+
+        for (index, token) in self.tokens.iter().enumerate() {
+            if token.kind == TokenKind::Newline {
+                lines.push(Line {
+                    text: &self.text[offset..token.span.start],
+                    tokens: &self.tokens[first..index],
+                    offset,
+                });
+                first = index + 1;
+                offset = token.span.end;
+            }
+        }
+        if offset < self.text.len() {
+            lines.push(Line {
+                text: &self.text[offset..],
+                tokens: &self.tokens[first..],
+                offset,
+            });
+        }
+
+        lines
+    }
+
+    /// The token containing the byte `offset` of the node
+    pub fn token_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens
+            .iter()
+            .find(|token| token.span.contains(&offset))
+    }
+}
+
+impl Line<'_> {
+    /// The comment of the line, `;` included
+    pub fn comment(&self) -> Option<&str> {
+        self.tokens
+            .iter()
+            .find(|token| token.kind == TokenKind::Comment)
+            .map(|token| &self.text[token.span.start - self.offset..token.span.end - self.offset])
+    }
+
+    /// Whether the line holds nothing but whitespace and a comment
+    pub fn is_trivia(&self) -> bool {
+        self.tokens.iter().all(|token| {
+            matches!(
+                token.kind,
+                TokenKind::Whitespace | TokenKind::Newline | TokenKind::Comment
+            )
+        })
+    }
+}
+
+/// One node for each blank or comment line of `span`
+fn trivia<'a>(
+    src: &'a str,
+    span: Range<usize>,
+    tokens: &'a [Token],
+) -> impl Iterator<Item = Node> + 'a {
+    src[span.clone()]
+        .split_inclusive('\n')
+        .scan(span.start, move |start, line| {
+            let line = *start..*start + line.len();
+            *start = line.end;
+            Some(Node::new(src, line, None, tokens))
+        })
+}
+
+/// Whitespace and line endings of `span`, which lies between the tokens the parser read
+fn layout(src: &str, span: Range<usize>) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut start: usize = span.start;
+    for line in src[span].split_inclusive('\n') {
+        let end = start + line.len();
+        let newline = if line.ends_with("\r\n") {
+            2
+        } else if line.ends_with('\n') {
+            1
+        } else {
+            0
+        };
+
+        if end - newline > start {
+            tokens.push(Token {
+                kind: TokenKind::Whitespace,
+                span: start..end - newline,
+            });
+        }
+        if newline > 0 {
+            tokens.push(Token {
+                kind: TokenKind::Newline,
+                span: end - newline..end,
+            });
+        }
+        start = end;
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SyntaxTree, TokenKind};
+
+    const NOTE: &str = "\
+; household\r
+include \"prices.kakei\"
+
+2026-03-14 \"Matsuya\"\t; lunch
+  Expenses:Food    1,200 jpy
+    ; paid in cash
+  Assets:Wallet
+~ monthly from 2026-01-25 Expenses:Rent 85000 JPY
+  Assets:Bank
+  ;no newline at the end";
+
+    #[test]
+    fn lossless() -> anyhow::Result<()> {
+        let tree = SyntaxTree::parse(NOTE)?;
+
+        assert_eq!(tree.to_string(), NOTE);
+        assert_eq!(tree.entries().count(), 3);
+        assert_eq!(
+            tree.entries().cloned().collect::<Vec<_>>(),
+            crate::parse(NOTE)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tokens() -> anyhow::Result<()> {
+        let tree = SyntaxTree::parse(NOTE)?;
+        let offset = NOTE.find("Expenses:Food").expect("the Matsuya posting");
+        let index = tree.node_at(offset).expect("a node at the posting");
+        let node = &tree.nodes[index];
+
+        let kinds: Vec<TokenKind> = node
+            .tokens
+            .iter()
+            .filter(|t| t.kind != TokenKind::Whitespace)
+            .map(|t| t.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Date,
+                TokenKind::String,
+                TokenKind::Comment,
+                TokenKind::Newline,
+                TokenKind::Account,
+                TokenKind::Number,
+                TokenKind::Currency,
+                TokenKind::Newline,
+                TokenKind::Comment,
+                TokenKind::Newline,
+                TokenKind::Account,
+                TokenKind::Newline,
+            ]
+        );
+
+        let start = offset - tree.offsets()[index];
+        let token = node.token_at(start + 3).map(|t| &node.text[t.span.clone()]);
+        assert_eq!(token, Some("Expenses:Food"));
+
+        let lines = node.lines();
+        assert_eq!(lines[0].comment(), Some("; lunch"));
+        assert!(lines[2].is_trivia());
+
+        let recurring = &tree.nodes[index + 1];
+        let kinds: Vec<TokenKind> = recurring.lines()[0]
+            .tokens
+            .iter()
+            .filter(|t| t.kind != TokenKind::Whitespace)
+            .map(|t| t.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Punctuation,
+                TokenKind::Keyword,
+                TokenKind::Keyword,
+                TokenKind::Date,
+                TokenKind::Account,
+                TokenKind::Number,
+                TokenKind::Currency,
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn japanese_tokens() -> anyhow::Result<()> {
+        let src = "令和8年3月14日 \"松屋\"\n  Expenses:Food  １２００円\n  Assets:Wallet\n";
+        let tree = SyntaxTree::parse(src)?;
+        let node = &tree.nodes[0];
+
+        let tokens: Vec<(TokenKind, &str)> = node
+            .tokens
+            .iter()
+            .filter(|t| !matches!(t.kind, TokenKind::Whitespace | TokenKind::Newline))
+            .map(|t| (t.kind, &node.text[t.span.clone()]))
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                (TokenKind::Date, "令和8年3月14日"),
+                (TokenKind::String, "\"松屋\""),
+                (TokenKind::Account, "Expenses:Food"),
+                (TokenKind::Number, "１２００"),
+                (TokenKind::Currency, "円"),
+                (TokenKind::Account, "Assets:Wallet"),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn replace_entry() -> anyhow::Result<()> {
+        let mut tree = SyntaxTree::parse(NOTE)?;
+        let index = tree
+            .nodes
+            .iter()
+            .position(|node| node.text.starts_with("2026-03-14"))
+            .expect("the Matsuya entry");
+
+        tree.replace(
+            index,
+            "2026-03-14 \"Sukiya\"\n  Expenses:Food  500 JPY\n  Assets:Wallet\n",
+        )?;
+
+        let expected = NOTE.replace(
+            "2026-03-14 \"Matsuya\"\t; lunch\n  Expenses:Food    1,200 jpy\n    ; paid in cash\n",
+            "2026-03-14 \"Sukiya\"\n  Expenses:Food  500 JPY\n",
+        );
+        assert_eq!(tree.to_string(), expected);
+
+        let two = "2026-03-15 price SATS 0.0145 JPY\n2026-03-16 price SATS 0.0146 JPY\n";
+        for text in [two, "; no entry\n", "\n2026-03-15 price SATS 0.0145 JPY\n"] {
+            assert!(tree.replace(index, text).is_err(), "{text:?}");
+        }
+        assert_eq!(tree.to_string(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn replace_without_line_ending() -> anyhow::Result<()> {
+        let src = "\
+2026-03-14 \"Matsuya\"\r
+  Expenses:Food  980 JPY\r
+  Assets:Wallet\r
+2026-03-15 \"Doutor\"\r
+  Expenses:Cafe  450 JPY\r
+  Assets:Wallet\r
+2026-03-16 \"Sukiya\"\r
+  Expenses:Food  500 JPY\r
+  Assets:Wallet\r
+";
+        let mut tree = SyntaxTree::parse(src)?;
+        let index = tree
+            .nodes
+            .iter()
+            .position(|node| node.text.starts_with("2026-03-15"))
+            .expect("the Doutor entry");
+
+        tree.replace(
+            index,
+            "2026-03-15 \"Doutor\"\r\n  Expenses:Cafe  520 JPY\r\n  Assets:Wallet",
+        )?;
+
+        let expected = src.replace("450 JPY", "520 JPY");
+        assert_eq!(tree.to_string(), expected);
+        assert_eq!(SyntaxTree::parse(&expected)?, tree);
+        Ok(())
+    }
+}
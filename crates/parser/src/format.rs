@@ -0,0 +1,440 @@
+//! Canonical formatting of kakeibo notes
+//!
+//! Entries are sorted by date and printed with their amounts aligned in one column. Comments
+//! stay with the entry they are written in or above, as found in the [`SyntaxTree`].
+
+use crate::{
+    ParseError,
+    cst::{Line, SyntaxTree},
+};
+use kakei_types::{
+    date::Period,
+    entry::{Entry, Posting},
+};
+
+/// Comments around one entry
+#[derive(Debug, Default, PartialEq, Clone)]
+struct Comments {
+    /// Comment lines above the entry
+    leading: Vec<String>,
+
+    /// Comment after the first line
+    header: Option<String>,
+
+    /// Comment lines above each posting, and the comment after it
+    postings: Vec<(Vec<String>, Option<String>)>,
+
+    /// Indented comment lines after the last posting
+    footer: Vec<String>,
+}
+
+/// A note split into entries and the comments belonging to them
+#[derive(Debug, Default)]
+struct Document {
+    /// Comment lines at the top of the file, separated from the first entry by a blank line
+    header: Vec<String>,
+    items: Vec<(Entry, Comments)>,
+
+    /// Comment lines after the last entry
+    footer: Vec<String>,
+}
+
+/// Formats a whole kakeibo note.
+pub fn format(src: &str) -> Result<String, ParseError> {
+    let mut document = Document::read(src)?;
+    document.items.sort_by_key(|(entry, _)| entry.date());
+
+    Ok(document.print())
+}
+
+impl Document {
+    fn read(src: &str) -> Result<Self, ParseError> {
+        let tree = SyntaxTree::parse(src)?;
+
+        let mut document = Self::default();
+        let mut pending: Vec<String> = Vec::new();
+
+        for node in &tree.nodes {
+            let lines = node.lines();
+            let Some(entry) = &node.entry else {
+                match lines.first().and_then(Line::comment) {
+                    Some(text) => pending.push(comment(text)),
+                    None if document.items.is_empty() && document.header.is_empty() => {
+                        document.header = std::mem::take(&mut pending);
+                    }
+                    None => {}
+                }
+                continue;
+            };
+
+            let mut comments = Comments {
+                leading: std::mem::take(&mut pending),
+                header: lines.first().and_then(Line::comment).map(comment),
+                ..Default::default()
+            };
+            for line in lines.iter().skip(1) {
+                if line.is_trivia() {
+                    pending.extend(line.comment().map(comment));
+                } else {
+                    comments
+                        .postings
+                        .push((std::mem::take(&mut pending), line.comment().map(comment)));
+                }
+            }
+            comments.footer = std::mem::take(&mut pending);
+
+            // The first posting of a recurring entry may be written on its first line
+            let postings = match entry {
+                Entry::Transaction(transaction) => transaction.postings.len(),
+                Entry::Recurring(recurring) => recurring.postings.len(),
+                _ => 0,
+            };
+            while comments.postings.len() < postings {
+                comments.postings.insert(0, Default::default());
+            }
+
+            document.items.push((entry.clone(), comments));
+        }
+
+        document.footer = pending;
+        Ok(document)
+    }
+
+    fn print(&self) -> String {
+        let postings = self.items.iter().flat_map(|(entry, _)| match entry {
+            Entry::Transaction(transaction) => transaction.postings.iter(),
+            Entry::Recurring(recurring) => recurring.postings.iter(),
+            _ => [].iter(),
+        });
+        let columns = postings
+            .filter_map(|p| p.amount.map(|a| (&p.account, a.value())))
+            .fold((0, 0), |(account, value), (name, v)| {
+                (
+                    account.max(name.chars().count()),
+                    value.max(v.to_string().len()),
+                )
+            });
+
+        let mut out = String::new();
+        for text in &self.header {
+            out.push_str(&format!("{text}\n"));
+        }
+
+        let mut previous: Option<&Entry> = None;
+        for (entry, comments) in &self.items {
+            let separated = previous.is_some_and(|p| is_multiline(p) || is_multiline(entry))
+                || (previous.is_none() && !self.header.is_empty());
+            if separated || (previous.is_some() && !comments.leading.is_empty()) {
+                out.push('\n');
+            }
+            previous = Some(entry);
+
+            for text in &comments.leading {
+                out.push_str(&format!("{text}\n"));
+            }
+            print_entry(&mut out, entry, comments, columns);
+        }
+
+        if !self.footer.is_empty() && !self.items.is_empty() {
+            out.push('\n');
+        }
+        for text in &self.footer {
+            out.push_str(&format!("{text}\n"));
+        }
+
+        out
+    }
+}
+
+fn is_multiline(entry: &Entry) -> bool {
+    matches!(entry, Entry::Transaction(_) | Entry::Recurring(_))
+}
+
+fn print_entry(out: &mut String, entry: &Entry, comments: &Comments, columns: (usize, usize)) {
+    let (header, postings): (String, &[Posting]) = match entry {
+        Entry::Include(include) => (format!("include \"{}\"", include.path), &[]),
+        Entry::Open(open) => {
+            let currencies: String = open.currencies.iter().map(|c| format!(" {c}")).collect();
+            (
+                format!("{} open {}{}", open.date, open.account, currencies),
+                &[],
+            )
+        }
+        Entry::Budget(budget) => {
+            let date = if budget.period == Period::Monthly && budget.date.day() == 1 {
+                format!("{:04}-{:02}", budget.date.year(), budget.date.month())
+            } else {
+                budget.date.to_string()
+            };
+            let period = match budget.period {
+                Period::Monthly => String::new(),
+                period => format!(" {period}"),
+            };
+            (
+                format!("{date} budget{period} {} {}", budget.account, budget.amount),
+                &[],
+            )
+        }
+        Entry::Price(price) => (price.to_string(), &[]),
+        Entry::Transaction(transaction) => (
+            format!("{} \"{}\"", transaction.date, transaction.payee),
+            &transaction.postings,
+        ),
+        Entry::Recurring(recurring) => {
+            let until = recurring
+                .until
+                .map_or(String::new(), |until| format!(" until {until}"));
+            (
+                format!(
+                    "~ {} from {}{} \"{}\"",
+                    recurring.period, recurring.start, until, recurring.payee
+                ),
+                &recurring.postings,
+            )
+        }
+    };
+
+    push_line(out, header, &comments.header);
+    for (index, posting) in postings.iter().enumerate() {
+        let (above, after) = comments
+            .postings
+            .get(index)
+            .map_or((&[][..], &None), |(above, after)| (above.as_slice(), after));
+        for text in above {
+            out.push_str(&format!("  {text}\n"));
+        }
+        push_line(out, print_posting(posting, columns), after);
+    }
+    for text in &comments.footer {
+        out.push_str(&format!("  {text}\n"));
+    }
+}
+
+/// `  Expenses:Food  980 JPY`, the amount right-aligned in the column
+fn print_posting(posting: &Posting, (account, value): (usize, usize)) -> String {
+    let Some(amount) = posting.amount else {
+        return format!("  {}", posting.account);
+    };
+
+    let mut line = format!(
+        "  {:<account$}  {:>value$} {}",
+        posting.account,
+        amount.value(),
+        amount.code(),
+    );
+    if let Some(cost) = posting.cost {
+        line.push_str(&format!(" {{{cost}}}"));
+    }
+    line
+}
+
+fn push_line(out: &mut String, line: String, comment: &Option<String>) {
+    out.push_str(&line);
+    if let Some(text) = comment {
+        out.push(' ');
+        out.push_str(text);
+    }
+    out.push('\n');
+}
+
+/// `;text` as `; text`
+fn comment(text: &str) -> String {
+    let text = text.trim_start_matches(';').trim();
+    if text.is_empty() {
+        ";".to_string()
+    } else {
+        format!("; {text}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Comments, Document, format};
+    use crate::cst::SyntaxTree;
+    use crate::parse;
+    use kakei_types::{
+        currency::{Amount, CurrencyCode},
+        date::{Date, Period},
+        entry::{Budget, Entry, Include, Open, Posting, Price, Recurring, Transaction},
+    };
+    use proptest::prelude::*;
+
+    #[test]
+    fn format_note() -> anyhow::Result<()> {
+        let src = "\
+; household
+
+2026-03-14 \"Matsuya\"   ;lunch
+  Expenses:Food 1,200 jpy
+  ; paid in cash
+    Assets:Wallet
+; opening
+2026-01-01 open Assets:Wallet JPY SATS
+2026-04-01 budget Expenses:Food 60000 JPY
+2026-04-06 budget weekly Expenses:Cafe 3000 JPY
+~ monthly from 2026-01-25 Expenses:Rent 85000 JPY ; rent
+  Assets:Bank
+; the end
+";
+        let formatted = format(src)?;
+
+        assert_eq!(
+            formatted,
+            "\
+; household
+
+; opening
+2026-01-01 open Assets:Wallet JPY SATS
+
+~ monthly from 2026-01-25 \"Expenses:Rent\" ; rent
+  Expenses:Rent  85000 JPY
+  Assets:Bank
+
+2026-03-14 \"Matsuya\" ; lunch
+  Expenses:Food   1200 JPY
+  ; paid in cash
+  Assets:Wallet
+
+2026-04 budget Expenses:Food 60000 JPY
+2026-04-06 budget weekly Expenses:Cafe 3000 JPY
+
+; the end
+"
+        );
+        assert_eq!(format(&formatted)?, formatted);
+        Ok(())
+    }
+
+    #[test]
+    fn format_japanese_notation() -> anyhow::Result<()> {
+        let src = "令和8年3月14日 \"松屋\"\n  Expenses:Food  １，２００円\n  Assets:Wallet\n";
+
+        assert_eq!(
+            format(src)?,
+            "2026-03-14 \"松屋\"\n  Expenses:Food  1200 JPY\n  Assets:Wallet\n"
+        );
+        Ok(())
+    }
+
+    fn date() -> impl Strategy<Value = Date> {
+        (2000..2100i32, 1..=12u32, 1..=28u32)
+            .prop_map(|(year, month, day)| Date::new(year, month, day).expect("valid date"))
+    }
+
+    fn account() -> impl Strategy<Value = String> {
+        prop::collection::vec("[A-Z食][A-Za-z0-9_費-]{0,7}", 1..4).prop_map(|s| s.join(":"))
+    }
+
+    fn currency() -> impl Strategy<Value = CurrencyCode> {
+        prop_oneof![Just(CurrencyCode::JPY), Just(CurrencyCode::SATS)]
+    }
+
+    fn amount() -> impl Strategy<Value = Amount> {
+        (currency(), -1_000_000_000..1_000_000_000i64)
+            .prop_map(|(code, value)| Amount::new(code, value))
+    }
+
+    fn period() -> impl Strategy<Value = Period> {
+        prop_oneof![
+            Just(Period::Weekly),
+            Just(Period::Monthly),
+            Just(Period::Yearly)
+        ]
+    }
+
+    fn payee() -> impl Strategy<Value = String> {
+        "[A-Za-z0-9 ;:.,&'ぁ-ん]{0,12}"
+    }
+
+    fn posting() -> impl Strategy<Value = Posting> {
+        (
+            account(),
+            prop::option::of((amount(), prop::option::of(amount()))),
+        )
+            .prop_map(|(account, amount)| Posting {
+                account,
+                amount: amount.map(|(amount, _)| amount),
+                cost: amount.and_then(|(_, cost)| cost),
+            })
+    }
+
+    fn entry() -> impl Strategy<Value = Entry> {
+        prop_oneof![
+            "[a-z]{1,8}\\.kakei".prop_map(|path| Entry::Include(Include { path })),
+            (date(), account(), prop::collection::vec(currency(), 0..3)).prop_map(
+                |(date, account, currencies)| Entry::Open(Open {
+                    date,
+                    account,
+                    currencies
+                })
+            ),
+            (date(), period(), account(), amount()).prop_map(|(date, period, account, amount)| {
+                Entry::Budget(Budget {
+                    date,
+                    period,
+                    account,
+                    amount,
+                })
+            }),
+            (date(), currency(), 1..1_000_000i64, 0..6usize, currency()).prop_map(
+                |(date, base, mantissa, scale, quote)| {
+                    let divisor = 10i64.pow(scale as u32);
+                    let rate = format!("{}.{:0>scale$}", mantissa / divisor, mantissa % divisor);
+                    Entry::Price(Price {
+                        date,
+                        base,
+                        rate: rate.parse().expect("positive rate"),
+                        quote,
+                    })
+                }
+            ),
+            (date(), payee(), prop::collection::vec(posting(), 1..4)).prop_map(
+                |(date, payee, postings)| Entry::Transaction(Transaction {
+                    date,
+                    payee,
+                    postings,
+                    metadata: Vec::new(),
+                    generated: false,
+                })
+            ),
+            (
+                period(),
+                date(),
+                prop::option::of(date()),
+                payee(),
+                prop::collection::vec(posting(), 0..3)
+            )
+                .prop_map(|(period, start, until, payee, postings)| {
+                    Entry::Recurring(Recurring {
+                        period,
+                        start,
+                        until,
+                        payee,
+                        postings,
+                    })
+                }),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip(entries in prop::collection::vec(entry(), 0..12)) {
+            let document = Document {
+                items: entries
+                    .iter()
+                    .map(|entry| (entry.clone(), Comments::default()))
+                    .collect(),
+                ..Default::default()
+            };
+            let printed = document.print();
+            prop_assert_eq!(parse(&printed)?, entries.clone());
+            prop_assert_eq!(SyntaxTree::parse(&printed)?.to_string(), printed.clone());
+
+            let mut sorted = entries;
+            sorted.sort_by_key(|entry| entry.date());
+            let formatted = format(&printed)?;
+            prop_assert_eq!(parse(&formatted)?, sorted);
+            prop_assert_eq!(format(&formatted)?, formatted);
+        }
+    }
+}
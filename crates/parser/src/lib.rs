@@ -1,15 +1,839 @@
 //! Kakei parser
 
-use kakei_types::currency::Currency;
-use kakei_types::query::Query;
+pub mod cst;
+pub mod format;
+
+use chumsky::{inspector::TruncateState, prelude::*};
+use cst::{Token, TokenKind};
+use kakei_types::{
+    currency::{Amount, CurrencyCode},
+    date::{Date, Period},
+    entry::{Budget, Entry, Include, Open, Posting, Price, Recurring, Transaction},
+    rate::Rate,
+};
+use std::ops::Range;
 use thiserror::Error;
 
-#[derive(Debug, Error)]
-pub enum ParseError {}
+#[derive(Debug, Error, PartialEq, Clone)]
+pub enum ParseError {
+    #[error("{line}:{column}: {message}")]
+    Syntax {
+        /// Byte range in the source
+        span: Range<usize>,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+}
+
+impl ParseError {
+    fn new(src: &str, span: Range<usize>, message: String) -> Self {
+        let before = src.get(..span.start).unwrap_or(src);
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+
+        Self::Syntax {
+            span,
+            line,
+            column,
+            message,
+        }
+    }
+}
+
+/// Every parser records the tokens it reads, dropping them again when it backtracks
+type Extra<'src> = extra::Full<Rich<'src, char>, TruncateState<Token>, ()>;
+
+/// `[date] payee amount [currency] [account [paid-from]]`, such as `lunch 980 food`
+///
+/// The omitted parts are inferred from the history of the payee.
+#[derive(Debug, PartialEq, Clone)]
+pub struct QuickEntry {
+    pub date: Option<Date>,
+    pub payee: String,
+    pub value: i64,
+    pub currency: Option<CurrencyCode>,
+
+    /// Part of an account name, such as `food` for `Expenses:Food`
+    pub account: Option<String>,
+    pub from: Option<String>,
+}
+
+/// Parses a whole kakeibo note.
+pub fn parse(src: &str) -> Result<Vec<Entry>, ParseError> {
+    Ok(parse_spanned(src)?
+        .into_iter()
+        .map(|(entry, _)| entry)
+        .collect())
+}
+
+/// Parses a whole kakeibo note, with the byte range of the lines each entry is written on.
+pub fn parse_spanned(src: &str) -> Result<Vec<(Entry, Range<usize>)>, ParseError> {
+    Ok(parse_tokens(src)?.0)
+}
+
+/// Entries with the byte range of their lines, and the tokens read in the order they are written
+type Tokens = (Vec<(Entry, Range<usize>)>, Vec<Token>);
+
+/// Parses a whole kakeibo note, keeping the tokens read.
+fn parse_tokens(src: &str) -> Result<Tokens, ParseError> {
+    let mut tokens = TruncateState::from(Vec::new());
+    let entries = note()
+        .parse_with_state(src, &mut tokens)
+        .into_result()
+        .map_err(|errors| {
+            let error = &errors[0];
+            ParseError::new(src, error.span().into_range(), error.to_string())
+        })?;
+
+    Ok((entries, tokens.0))
+}
+
+/// Parses a one-line entry written on the command line.
+pub fn parse_quick(src: &str) -> Result<QuickEntry, ParseError> {
+    quick()
+        .parse_with_state(src, &mut TruncateState::from(Vec::new()))
+        .into_result()
+        .map_err(|errors| {
+            let error = &errors[0];
+            ParseError::new(src, error.span().into_range(), error.to_string())
+        })
+}
+
+fn note<'src>() -> impl Parser<'src, &'src str, Vec<(Entry, Range<usize>)>, Extra<'src>> {
+    let blank = inline_ws().then(comment().or_not()).then(text::newline());
+
+    let include = token(TokenKind::Keyword, just("include"))
+        .ignore_then(ws().ignore_then(quoted()))
+        .then_ignore(eol())
+        .map(|path| Entry::Include(Include { path }));
+
+    let entry = choice((include, budget(), recurring(), entry()))
+        .map_with(|entry, e| (entry, e.span().into_range()));
+
+    choice((blank.to(None), entry.map(Some)))
+        .repeated()
+        .collect::<Vec<Option<(Entry, Range<usize>)>>>()
+        .then_ignore(inline_ws().then(comment().or_not()))
+        .then_ignore(end())
+        .map(|entries| entries.into_iter().flatten().collect())
+}
+
+fn entry<'src>() -> impl Parser<'src, &'src str, Entry, Extra<'src>> {
+    let open = token(TokenKind::Keyword, just("open"))
+        .ignore_then(ws().ignore_then(account()))
+        .then(ws().ignore_then(currency()).repeated().collect::<Vec<_>>())
+        .then_ignore(eol());
+
+    let price = token(TokenKind::Keyword, just("price"))
+        .ignore_then(ws().ignore_then(currency()))
+        .then(ws().ignore_then(rate()))
+        .then(ws().ignore_then(currency()))
+        .then_ignore(eol());
+
+    let transaction = payee()
+        .then_ignore(eol())
+        .then(posting_line().repeated().at_least(1).collect::<Vec<_>>());
+
+    date()
+        .then_ignore(ws())
+        .then(choice((
+            open.map(EntryKind::Open),
+            price.map(EntryKind::Price),
+            transaction.map(EntryKind::Transaction),
+        )))
+        .map(|(date, kind)| kind.into_entry(date))
+}
+
+/// `2026-04 budget Expenses:Food 60000 JPY`
+fn budget<'src>() -> impl Parser<'src, &'src str, Entry, Extra<'src>> {
+    choice((date(), month()))
+        .then_ignore(ws().then(token(TokenKind::Keyword, just("budget"))))
+        .then(ws().ignore_then(period()).or_not())
+        .then(ws().ignore_then(account()))
+        .then(ws().ignore_then(amount()))
+        .then_ignore(eol())
+        .map(|(((date, period), account), amount)| {
+            Entry::Budget(Budget {
+                date,
+                period: period.unwrap_or(Period::Monthly),
+                account,
+                amount,
+            })
+        })
+}
+
+/// `~ monthly from 2026-01-25 until 2026-12-31 "Rent"` and its postings
+fn recurring<'src>() -> impl Parser<'src, &'src str, Entry, Extra<'src>> {
+    token(TokenKind::Punctuation, just('~'))
+        .ignore_then(ws().ignore_then(period()))
+        .then(
+            ws().then(token(TokenKind::Keyword, just("from")))
+                .ignore_then(ws().ignore_then(date())),
+        )
+        .then(
+            ws().then(token(TokenKind::Keyword, just("until")))
+                .ignore_then(ws().ignore_then(date()))
+                .or_not(),
+        )
+        .then(ws().ignore_then(payee()).or_not())
+        .then(ws().ignore_then(posting()).or_not())
+        .then_ignore(eol())
+        .then(posting_line().repeated().collect::<Vec<_>>())
+        .map(|(((((period, start), until), payee), first), rest)| {
+            let postings: Vec<Posting> = first
+                .into_iter()
+                .chain(rest.into_iter().filter_map(|line| match line {
+                    PostingLine::Posting(posting) => Some(posting),
+                    PostingLine::Metadata(..) | PostingLine::Comment => None,
+                }))
+                .collect();
+            let payee = payee
+                .or_else(|| postings.first().map(|p| p.account.clone()))
+                .unwrap_or_default();
+
+            Entry::Recurring(Recurring {
+                period,
+                start,
+                until,
+                payee,
+                postings,
+            })
+        })
+}
+
+fn quick<'src>() -> impl Parser<'src, &'src str, QuickEntry, Extra<'src>> {
+    let word = none_of(" \t\r\n\"").repeated().at_least(1).to_slice();
+    let payee_words = word
+        .filter(|w: &&str| {
+            integer()
+                .then(yen().or_not())
+                .parse_with_state(w, &mut TruncateState::from(Vec::new()))
+                .has_errors()
+        })
+        .separated_by(ws())
+        .at_least(1)
+        .collect::<Vec<&str>>()
+        .map(|words| words.join(" "));
+    let currency = word.try_map(|w: &str, span| match w {
+        "円" => Ok(CurrencyCode::JPY),
+        w => w
+            .parse::<CurrencyCode>()
+            .map_err(|e| Rich::custom(span, e.to_string())),
+    });
+    let hint = word.map(ToString::to_string).labelled("account");
+
+    inline_ws()
+        .ignore_then(date().then_ignore(ws()).or_not())
+        .then(choice((payee(), payee_words)).labelled("payee"))
+        .then(ws().ignore_then(integer()))
+        .then(choice((yen(), ws().ignore_then(currency))).or_not())
+        .then(ws().ignore_then(hint).or_not())
+        .then(ws().ignore_then(hint).or_not())
+        .then_ignore(inline_ws())
+        .then_ignore(end())
+        .map(
+            |(((((date, payee), value), currency), account), from)| QuickEntry {
+                date,
+                payee,
+                value,
+                currency,
+                account,
+                from,
+            },
+        )
+}
+
+/// `Expenses:Food  980 JPY`, optionally followed by `{cost}`
+fn posting<'src>() -> impl Parser<'src, &'src str, Posting, Extra<'src>> + Clone {
+    let cost = amount()
+        .padded_by(inline_ws())
+        .delimited_by(
+            token(TokenKind::Punctuation, just('{')),
+            token(TokenKind::Punctuation, just('}')),
+        )
+        .labelled("cost");
+
+    account()
+        .then(
+            ws().ignore_then(amount())
+                .then(ws().ignore_then(cost).or_not())
+                .or_not(),
+        )
+        .map(|(account, amount)| Posting {
+            account,
+            amount: amount.map(|(amount, _)| amount),
+            cost: amount.and_then(|(_, cost)| cost),
+        })
+}
+
+/// One indented line of a transaction
+#[derive(Debug, Clone)]
+enum PostingLine {
+    Posting(Posting),
+
+    /// `; ref: 20260314-0001`, a comment naming its key as ledger does
+    Metadata(String, String),
+    Comment,
+}
+
+/// An indented posting, metadata or comment
+fn posting_line<'src>() -> impl Parser<'src, &'src str, PostingLine, Extra<'src>> + Clone {
+    let key = any()
+        .filter(|c: &char| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .repeated()
+        .at_least(1)
+        .to_slice();
+    let metadata = just(';')
+        .ignore_then(inline_ws())
+        .ignore_then(key)
+        .then_ignore(just(':').then(ws()))
+        .then(none_of("\r\n").repeated().at_least(1).to_slice())
+        .map(|(key, value): (&str, &str)| {
+            PostingLine::Metadata(key.to_string(), value.trim_end().to_string())
+        });
+
+    ws().ignore_then(choice((
+        token(TokenKind::Comment, metadata),
+        comment().to(PostingLine::Comment),
+        posting().map(PostingLine::Posting),
+    )))
+    .then_ignore(eol())
+}
+
+/// `weekly`, `monthly` or `yearly`
+fn period<'src>() -> impl Parser<'src, &'src str, Period, Extra<'src>> + Clone {
+    let period = choice((
+        just("weekly").to(Period::Weekly),
+        just("monthly").to(Period::Monthly),
+        just("yearly").to(Period::Yearly),
+    ));
+
+    token(TokenKind::Keyword, period).labelled("period")
+}
+
+enum EntryKind {
+    Open((String, Vec<CurrencyCode>)),
+    Price(((CurrencyCode, Rate), CurrencyCode)),
+    Transaction((String, Vec<PostingLine>)),
+}
+
+impl EntryKind {
+    fn into_entry(self, date: Date) -> Entry {
+        match self {
+            Self::Open((account, currencies)) => Entry::Open(Open {
+                date,
+                account,
+                currencies,
+            }),
+            Self::Price(((base, rate), quote)) => Entry::Price(Price {
+                date,
+                base,
+                rate,
+                quote,
+            }),
+            Self::Transaction((payee, lines)) => {
+                let mut postings: Vec<Posting> = Vec::new();
+                let mut metadata: Vec<(String, String)> = Vec::new();
+                for line in lines {
+                    match line {
+                        PostingLine::Posting(posting) => postings.push(posting),
+                        PostingLine::Metadata(key, value) => metadata.push((key, value)),
+                        PostingLine::Comment => {}
+                    }
+                }
+
+                Entry::Transaction(Transaction {
+                    date,
+                    payee,
+                    postings,
+                    metadata,
+                    generated: false,
+                })
+            }
+        }
+    }
+}
+
+/// `parser`, recording what it reads as a token of `kind`
+fn token<'src, O>(
+    kind: TokenKind,
+    parser: impl Parser<'src, &'src str, O, Extra<'src>> + Clone,
+) -> impl Parser<'src, &'src str, O, Extra<'src>> + Clone {
+    // Unlike `map_with`, `try_map_with` also runs where the output is ignored, such as a comment
+    parser.try_map_with(move |output, e| {
+        let span = e.span().into_range();
+        e.state().push(Token { kind, span });
+        Ok(output)
+    })
+}
+
+/// One or more spaces or tabs
+fn ws<'src>() -> impl Parser<'src, &'src str, (), Extra<'src>> + Clone {
+    one_of(" \t").repeated().at_least(1).ignored()
+}
+
+fn inline_ws<'src>() -> impl Parser<'src, &'src str, (), Extra<'src>> + Clone {
+    one_of(" \t").repeated().ignored()
+}
+
+/// `; comment`
+fn comment<'src>() -> impl Parser<'src, &'src str, (), Extra<'src>> + Clone {
+    token(
+        TokenKind::Comment,
+        just(';').then(none_of("\r\n").repeated()),
+    )
+    .ignored()
+}
+
+/// End of a line, with an optional trailing comment
+fn eol<'src>() -> impl Parser<'src, &'src str, (), Extra<'src>> + Clone {
+    inline_ws()
+        .then(comment().or_not())
+        .then(text::newline().or(end()))
+        .ignored()
+}
+
+/// An ASCII or full-width digit
+fn is_digit(c: &char) -> bool {
+    c.is_ascii_digit() || ('０'..='９').contains(c)
+}
+
+/// `text` with full-width ASCII characters, such as `１２００` or `－`, as their ASCII forms
+fn halfwidth(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            c => c,
+        })
+        .collect()
+}
+
+/// Exactly `n` ASCII or full-width digits
+fn digits<'src>(n: usize) -> impl Parser<'src, &'src str, u32, Extra<'src>> + Clone {
+    any()
+        .filter(is_digit)
+        .repeated()
+        .exactly(n)
+        .to_slice()
+        .map(|s: &str| halfwidth(s).parse().unwrap_or(0))
+}
+
+/// One to `n` ASCII or full-width digits, such as the month of `2026年3月14日`
+fn number<'src>(n: usize) -> impl Parser<'src, &'src str, u32, Extra<'src>> + Clone {
+    any()
+        .filter(is_digit)
+        .repeated()
+        .at_least(1)
+        .at_most(n)
+        .to_slice()
+        .map(|s: &str| halfwidth(s).parse().unwrap_or(0))
+}
+
+/// Japanese eras since the Meiji restoration, with the day each began
+const ERAS: [(&str, i32, u32, u32); 5] = [
+    ("明治", 1868, 10, 23),
+    ("大正", 1912, 7, 30),
+    ("昭和", 1926, 12, 25),
+    ("平成", 1989, 1, 8),
+    ("令和", 2019, 5, 1),
+];
+
+/// `2026年`, `令和8年` or `令和元年`, with the index of the era in [`ERAS`]
+fn year<'src>() -> impl Parser<'src, &'src str, (Option<usize>, u32), Extra<'src>> + Clone {
+    let era = choice((
+        just(ERAS[0].0).to(0),
+        just(ERAS[1].0).to(1),
+        just(ERAS[2].0).to(2),
+        just(ERAS[3].0).to(3),
+        just(ERAS[4].0).to(4),
+    ));
+
+    choice((
+        era.then(choice((just('元').to(1), number(2))))
+            .map(|(era, year)| (Some(era), year)),
+        digits(4).map(|year| (None, year)),
+    ))
+    .then_ignore(just('年'))
+}
+
+/// The date of `day` in `month` of `year`, checking an era date falls within the era.
+fn calendar_date((era, year): (Option<usize>, u32), month: u32, day: u32) -> Result<Date, String> {
+    let year: i32 = match era {
+        Some(era) => {
+            let (name, first, began_month, began_day) = ERAS[era];
+            let year: i32 = first + year as i32 - 1;
+            if (year, month, day) < (first, began_month, began_day) {
+                return Err(format!(
+                    "{name} began on {first:04}-{began_month:02}-{began_day:02}"
+                ));
+            }
+            if let Some((next, first, began_month, began_day)) = ERAS.get(era + 1)
+                && (year, month, day) >= (*first, *began_month, *began_day)
+            {
+                return Err(format!(
+                    "{name} ended when {next} began on {first:04}-{began_month:02}-{began_day:02}"
+                ));
+            }
+            year
+        }
+        None => year as i32,
+    };
+
+    Date::new(year, month, day).map_err(|error| error.to_string())
+}
+
+/// `2026-03-14`, `2026年3月14日` or `令和8年3月14日`
+fn date<'src>() -> impl Parser<'src, &'src str, Date, Extra<'src>> + Clone {
+    let iso = digits(4)
+        .then_ignore(just('-'))
+        .then(digits(2))
+        .then_ignore(just('-'))
+        .then(digits(2))
+        .map(|((year, month), day)| ((None, year), month, day));
+    let japanese = year()
+        .then(number(2))
+        .then_ignore(just('月'))
+        .then(number(2))
+        .then_ignore(just('日'))
+        .map(|((year, month), day)| (year, month, day));
+
+    let date = choice((iso, japanese)).validate(|(year, month, day), e, emitter| {
+        calendar_date(year, month, day).unwrap_or_else(|error| {
+            emitter.emit(Rich::custom(e.span(), error));
+            Date::from_days(0)
+        })
+    });
+
+    token(TokenKind::Date, date).labelled("date")
+}
+
+/// `2026-04` or `2026年4月`, the first day of the month
+fn month<'src>() -> impl Parser<'src, &'src str, Date, Extra<'src>> + Clone {
+    let iso = digits(4)
+        .then_ignore(just('-'))
+        .then(digits(2))
+        .map(|(year, month)| ((None, year), month));
+    let japanese = year().then(number(2)).then_ignore(just('月'));
+
+    let month = choice((iso, japanese)).validate(|(year, month), e, emitter| {
+        calendar_date(year, month, 1).unwrap_or_else(|error| {
+            emitter.emit(Rich::custom(e.span(), error));
+            Date::from_days(0)
+        })
+    });
+
+    token(TokenKind::Date, month).labelled("month")
+}
+
+/// `Expenses:Food`
+fn account<'src>() -> impl Parser<'src, &'src str, String, Extra<'src>> + Clone {
+    let segment = any()
+        .filter(|c: &char| c.is_alphanumeric() || *c == '-' || *c == '_')
+        .repeated()
+        .at_least(1);
+
+    let account = segment.separated_by(just(':')).at_least(1).to_slice();
+
+    token(TokenKind::Account, account)
+        .map(ToString::to_string)
+        .labelled("account")
+}
+
+/// `JPY`, in any case, or `円`
+fn currency<'src>() -> impl Parser<'src, &'src str, CurrencyCode, Extra<'src>> + Clone {
+    let code = any()
+        .filter(|c: &char| c.is_ascii_alphabetic())
+        .repeated()
+        .at_least(1)
+        .to_slice()
+        .validate(|code: &str, e, emitter| {
+            code.to_ascii_uppercase()
+                .parse::<CurrencyCode>()
+                .unwrap_or_else(|error| {
+                    emitter.emit(Rich::custom(e.span(), error.to_string()));
+                    CurrencyCode::JPY
+                })
+        });
+
+    choice((yen(), token(TokenKind::Currency, code))).labelled("currency")
+}
+
+/// `円`, written right after the number as in `1200円`
+fn yen<'src>() -> impl Parser<'src, &'src str, CurrencyCode, Extra<'src>> + Clone {
+    token(TokenKind::Currency, just('円')).to(CurrencyCode::JPY)
+}
+
+/// `-1,200` or `－１，２００`
+fn integer<'src>() -> impl Parser<'src, &'src str, i64, Extra<'src>> + Clone {
+    let integer = one_of("-－")
+        .or_not()
+        .then(any().filter(is_digit))
+        .then(
+            any()
+                .filter(|c: &char| is_digit(c) || matches!(c, ',' | '，'))
+                .repeated(),
+        )
+        .to_slice();
+
+    token(TokenKind::Number, integer)
+        .validate(|s: &str, e, emitter| {
+            halfwidth(s).replace(',', "").parse().unwrap_or_else(|_| {
+                emitter.emit(Rich::custom(e.span(), format!("invalid number: {s}")));
+                0
+            })
+        })
+        .labelled("number")
+}
+
+/// `1200 JPY` or `1200円`
+fn amount<'src>() -> impl Parser<'src, &'src str, Amount, Extra<'src>> + Clone {
+    integer()
+        .then(choice((yen(), ws().ignore_then(currency()))))
+        .map(|(value, code)| Amount::new(code, value))
+}
+
+/// `0.0145`
+fn rate<'src>() -> impl Parser<'src, &'src str, Rate, Extra<'src>> + Clone {
+    let rate = any()
+        .filter(|c: &char| c.is_ascii_digit() || *c == '.')
+        .repeated()
+        .at_least(1)
+        .to_slice();
+
+    token(TokenKind::Number, rate)
+        .try_map(|s: &str, span| {
+            s.parse::<Rate>()
+                .map_err(|e| Rich::custom(span, e.to_string()))
+        })
+        .labelled("rate")
+}
+
+/// `"prices.kakei"`
+fn quoted<'src>() -> impl Parser<'src, &'src str, String, Extra<'src>> + Clone {
+    let quoted = none_of("\"\r\n")
+        .repeated()
+        .to_slice()
+        .delimited_by(just('"'), just('"'));
+
+    token(TokenKind::String, quoted).map(ToString::to_string)
+}
+
+/// `"Matsuya"`
+fn payee<'src>() -> impl Parser<'src, &'src str, String, Extra<'src>> + Clone {
+    quoted().labelled("payee")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParseError, QuickEntry, parse, parse_quick};
+    use kakei_types::{
+        currency::{Amount, CurrencyCode, JPY, SATS},
+        date::{Date, Period},
+        entry::{Budget, Entry, Include, Open, Posting, Price, Transaction},
+    };
+
+    #[test]
+    fn entries() -> anyhow::Result<()> {
+        let src = "\
+; household
+include \"prices.kakei\"
+2026-01-01 open Assets:Wallet JPY SATS
+
+2026-03-14 price SATS 0.0145 JPY
+2026-03-14 \"Matsuya\" ; lunch
+  Expenses:Food  1,200 JPY
+  ; paid in cash
+  Assets:Wallet
+";
+        let entries = parse(src)?;
+
+        assert_eq!(
+            entries,
+            vec![
+                Entry::Include(Include {
+                    path: "prices.kakei".to_string(),
+                }),
+                Entry::Open(Open {
+                    date: Date::new(2026, 1, 1)?,
+                    account: "Assets:Wallet".to_string(),
+                    currencies: vec![CurrencyCode::JPY, CurrencyCode::SATS],
+                }),
+                Entry::Price(Price {
+                    date: Date::new(2026, 3, 14)?,
+                    base: CurrencyCode::SATS,
+                    rate: "0.0145".parse()?,
+                    quote: CurrencyCode::JPY,
+                }),
+                Entry::Transaction(Transaction {
+                    date: Date::new(2026, 3, 14)?,
+                    payee: "Matsuya".to_string(),
+                    postings: vec![
+                        Posting {
+                            account: "Expenses:Food".to_string(),
+                            amount: Some(Amount::JPY(JPY(1200))),
+                            cost: None,
+                        },
+                        Posting {
+                            account: "Assets:Wallet".to_string(),
+                            amount: None,
+                            cost: None,
+                        },
+                    ],
+                    metadata: Vec::new(),
+                    generated: false,
+                }),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn budgets() -> anyhow::Result<()> {
+        let src = "2026-04 budget Expenses:Food 60000 JPY\n2026-04-06 budget weekly Expenses:Cafe 3000 JPY\n";
+        let entries = parse(src)?;
+
+        assert_eq!(
+            entries,
+            vec![
+                Entry::Budget(Budget {
+                    date: Date::new(2026, 4, 1)?,
+                    period: Period::Monthly,
+                    account: "Expenses:Food".to_string(),
+                    amount: Amount::JPY(JPY(60000)),
+                }),
+                Entry::Budget(Budget {
+                    date: Date::new(2026, 4, 6)?,
+                    period: Period::Weekly,
+                    account: "Expenses:Cafe".to_string(),
+                    amount: Amount::JPY(JPY(3000)),
+                }),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn recurring() -> anyhow::Result<()> {
+        let src = "\
+~ monthly from 2026-01-25 Expenses:Rent 85000 JPY
+  Assets:Bank
+~ yearly from 2026-06-30 until 2030-06-30 \"Insurance\"
+  Expenses:Insurance  120000 JPY
+  Assets:Bank
+";
+        let entries = parse(src)?;
+        let [Entry::Recurring(rent), Entry::Recurring(insurance)] = &entries[..] else {
+            panic!("unexpected entries: {entries:?}");
+        };
+
+        assert_eq!(rent.period, Period::Monthly);
+        assert_eq!(rent.start, Date::new(2026, 1, 25)?);
+        assert_eq!(rent.payee, "Expenses:Rent");
+        assert_eq!(rent.postings.len(), 2);
+        assert_eq!(insurance.period, Period::Yearly);
+        assert_eq!(insurance.until, Some(Date::new(2030, 6, 30)?));
+        assert_eq!(insurance.payee, "Insurance");
+        Ok(())
+    }
+
+    #[test]
+    fn posting_cost() -> anyhow::Result<()> {
+        let src = "2026-03-14 \"Buy\"\n  Assets:Wallet  100000 SATS { 1450 JPY }\n  Assets:Bank\n";
+        let Entry::Transaction(transaction) = &parse(src)?[0] else {
+            panic!("not a transaction");
+        };
+
+        assert_eq!(
+            transaction.postings[0].amount,
+            Some(Amount::SATS(SATS(100_000)))
+        );
+        assert_eq!(transaction.postings[0].cost, Some(Amount::JPY(JPY(1450))));
+        Ok(())
+    }
+
+    #[test]
+    fn syntax_error_position() {
+        let src = "2026-01-01 open Assets:Wallet JPY\n2026-02-30 price SATS 0.0145 JPY\n";
+
+        match parse(src) {
+            Err(ParseError::Syntax { line, column, .. }) => assert_eq!((line, column), (2, 1)),
+            v => panic!("unexpected result: {v:?}"),
+        }
+    }
+
+    #[test]
+    fn japanese_notation() -> anyhow::Result<()> {
+        let src = "\
+２０２６年３月１４日 \"松屋\"
+  Expenses:Food  １，２００円
+  Assets:Wallet
+令和8年3月15日 \"すき家\"
+  Expenses:Food  -500 円
+  Assets:Wallet
+令和元年5月1日 open Assets:Bank 円
+2026年4月 budget Expenses:Food 60000円
+";
+        let entries = parse(src)?;
+        let dates: Vec<Date> = entries.iter().filter_map(Entry::date).collect();
+        assert_eq!(
+            dates,
+            vec![
+                Date::new(2026, 3, 14)?,
+                Date::new(2026, 3, 15)?,
+                Date::new(2019, 5, 1)?,
+                Date::new(2026, 4, 1)?,
+            ]
+        );
+
+        let [
+            Entry::Transaction(matsuya),
+            Entry::Transaction(sukiya),
+            Entry::Open(bank),
+            Entry::Budget(budget),
+        ] = &entries[..]
+        else {
+            panic!("unexpected entries: {entries:?}");
+        };
+        assert_eq!(matsuya.postings[0].amount, Some(Amount::JPY(JPY(1200))));
+        assert_eq!(sukiya.postings[0].amount, Some(Amount::JPY(JPY(-500))));
+        assert_eq!(bank.currencies, vec![CurrencyCode::JPY]);
+        assert_eq!(budget.amount, Amount::JPY(JPY(60000)));
+
+        // 平成 ended on 2019-04-30, and 令和 began the next day
+        assert!(parse("平成31年5月1日 open Assets:Bank\n").is_err());
+        assert!(parse("令和元年4月30日 open Assets:Bank\n").is_err());
+        assert_eq!(parse_quick("ランチ ９８０円 food")?.value, 980);
+        Ok(())
+    }
 
-pub fn parse<C>(src: &str, currency: C) -> Result<Query<C>, ParseError>
-where
-    C: Currency,
-{
-    todo!()
+    #[test]
+    fn quick_entry() -> anyhow::Result<()> {
+        assert_eq!(
+            parse_quick("lunch 980 food")?,
+            QuickEntry {
+                date: None,
+                payee: "lunch".to_string(),
+                value: 980,
+                currency: None,
+                account: Some("food".to_string()),
+                from: None,
+            }
+        );
+        assert_eq!(
+            parse_quick("2026-04-01 \"Seven Eleven\" 1,200 JPY Expenses:Food wallet")?,
+            QuickEntry {
+                date: Some(Date::new(2026, 4, 1)?),
+                payee: "Seven Eleven".to_string(),
+                value: 1200,
+                currency: Some(CurrencyCode::JPY),
+                account: Some("Expenses:Food".to_string()),
+                from: Some("wallet".to_string()),
+            }
+        );
+        assert_eq!(parse_quick("book off 300")?.payee, "book off");
+        assert!(parse_quick("lunch").is_err());
+        assert!(parse_quick("lunch 980 food wallet extra").is_err());
+        Ok(())
+    }
 }
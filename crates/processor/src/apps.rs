@@ -0,0 +1,463 @@
+//! ----- Household-budget apps -----
+//!
+//! Zaim and MoneyForward ME export every record of every account in one CSV, filed under two
+//! levels of their own categories, 大項目 and 中項目. A [`Mapping`] names the kakei account of
+//! each category and each account of the app.
+//!
+//! Zaim writes a transfer as one record from 支払元 to 入金先. MoneyForward ME writes one record in
+//! each account, which are paired by date and amount. A transfer to an account missing from the
+//! export goes to the account of its category, or to [`UNPAIRED_TRANSFERS`].
+
+use crate::import::{
+    Column, ImportError, REFERENCE, Statement, parse_date, parse_number, record, uncategorized,
+};
+use kakei_types::{date::Date, entry::Transaction};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Account of transfers whose other account is not in the export
+pub const UNPAIRED_TRANSFERS: &str = "Assets:Transfers";
+
+/// Metadata key of the memo of a record
+pub const MEMO: &str = "memo";
+
+/// Metadata key of the item of a Zaim record, 品目
+pub const ITEM: &str = "item";
+
+/// Kakei accounts of the categories and accounts of an app. A category is written as
+/// `大項目/中項目`, or as `大項目` for all of its sub-categories.
+///
+/// ```toml
+/// [categories]
+/// "食費/外食" = "Expenses:Food:EatingOut"
+/// "食費" = "Expenses:Food"
+/// "収入/給与" = "Income:Salary"
+///
+/// [accounts]
+/// "お財布" = "Assets:Wallet"
+/// "三井住友銀行" = "Assets:Bank:SMBC"
+/// "楽天カード" = "Liabilities:Card:Rakuten"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Mapping {
+    pub categories: BTreeMap<String, String>,
+    pub accounts: BTreeMap<String, String>,
+}
+
+impl Mapping {
+    pub fn parse(text: &str) -> Result<Self, ImportError> {
+        toml::from_str(text).map_err(|e| ImportError::Mapping(e.message().to_string()))
+    }
+
+    /// The account of `sub` under `major`, or of `major` when `sub` has none
+    pub fn category(&self, major: &str, sub: &str) -> Option<&str> {
+        self.categories
+            .get(&format!("{major}/{sub}"))
+            .or_else(|| self.categories.get(major))
+            .map(String::as_str)
+    }
+}
+
+/// Transactions read from an app, by the account of the app each was recorded in
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AppStatements {
+    pub statements: Vec<Statement>,
+
+    /// Records left out of the totals of the app, or adjusting a balance, which were skipped
+    pub skipped: usize,
+}
+
+/// Reads a CSV of Zaim. Records left out of its totals, 集計に含めない, are read only with
+/// `all`, and balance adjustments are skipped.
+pub fn read_zaim(text: &str, mapping: &Mapping, all: bool) -> Result<AppStatements, ImportError> {
+    let table = Table::read(text)?;
+    let date = table.column(&["日付"])?;
+    let method = table.column(&["方法"])?;
+    let major = table.column(&["カテゴリ"])?;
+    let sub = table.column(&["カテゴリの内訳"])?;
+    let from = table.column(&["支払元"])?;
+    let to = table.column(&["入金先"])?;
+    let item = table.column(&["品目"])?;
+    let memo = table.column(&["メモ"])?;
+    let shop = table.column(&["お店"])?;
+    let income = table.column(&["収入"])?;
+    let payment = table.column(&["支出"])?;
+    let transfer = table.column(&["振替"])?;
+    let currency = table.column(&["通貨"]).ok();
+    let totals = table.column(&["集計の設定"]).ok();
+
+    let mut accounts = Accounts::new(mapping);
+    let mut records: Vec<(String, Transaction)> = Vec::new();
+    let mut skipped: usize = 0;
+    for row in &table.rows {
+        let date: Date = row.date(date)?;
+        if let Some(column) = currency
+            && !matches!(row.get(column), "" | "JPY")
+        {
+            return Err(ImportError::Currency(row.get(column).to_string()));
+        }
+        if totals.is_some_and(|column| row.get(column).contains("含めない")) && !all {
+            skipped += 1;
+            continue;
+        }
+
+        let mut metadata: Vec<(String, String)> = Vec::new();
+        for (key, column) in [(ITEM, item), (MEMO, memo)] {
+            if !row.get(column).is_empty() {
+                metadata.push((key.to_string(), row.get(column).to_string()));
+            }
+        }
+
+        // A transfer has neither a shop nor a category to name it
+        let (account, change, category, payee): (&str, i64, Option<String>, &str) =
+            match row.get(method) {
+                "payment" | "支出" => (row.get(from), -row.amount(payment)?, None, ""),
+                "income" | "収入" => (row.get(to), row.amount(income)?, None, ""),
+                "transfer" | "振替" => (
+                    row.get(from),
+                    -row.amount(transfer)?,
+                    accounts.get(row.get(to)).map(ToString::to_string),
+                    "振替",
+                ),
+                _ => {
+                    skipped += 1;
+                    continue;
+                }
+            };
+        let payee: &str = [shop, item, sub, major]
+            .into_iter()
+            .map(|column| row.get(column))
+            .find(|text| !text.is_empty() && *text != "-")
+            .unwrap_or(payee);
+        let Some(account) = accounts.get(account).map(ToString::to_string) else {
+            continue;
+        };
+        let category: String = category.unwrap_or_else(|| {
+            mapping
+                .category(row.get(major), row.get(sub))
+                .unwrap_or_else(|| uncategorized(change))
+                .to_string()
+        });
+
+        records.push((
+            account.clone(),
+            record(date, payee, change, &account, &category, metadata),
+        ));
+    }
+
+    accounts.check()?;
+    Ok(AppStatements {
+        statements: statements(records),
+        skipped,
+    })
+}
+
+/// Reads a CSV of MoneyForward ME. Records left out of its totals, 計算対象 0, are read only
+/// with `all`, except transfers, which never count in the totals.
+pub fn read_moneyforward(
+    text: &str,
+    mapping: &Mapping,
+    all: bool,
+) -> Result<AppStatements, ImportError> {
+    let table = Table::read(text)?;
+    let counted = table.column(&["計算対象"])?;
+    let date = table.column(&["日付"])?;
+    let content = table.column(&["内容"])?;
+    let amount = table.column(&["金額（円）", "金額(円)"])?;
+    let institution = table.column(&["保有金融機関"])?;
+    let major = table.column(&["大項目"])?;
+    let sub = table.column(&["中項目"])?;
+    let memo = table.column(&["メモ"])?;
+    let transfer = table.column(&["振替"])?;
+    let id = table.column(&["ID"]).ok();
+
+    let mut accounts = Accounts::new(mapping);
+    let mut records: Vec<(String, Transaction)> = Vec::new();
+    // Transfers waiting for the record of the other account: account, change and transaction
+    let mut transfers: Vec<(String, i64, Transaction)> = Vec::new();
+    let mut skipped: usize = 0;
+    for row in &table.rows {
+        let is_transfer: bool = row.get(transfer) == "1";
+        if !is_transfer && row.get(counted) == "0" && !all {
+            skipped += 1;
+            continue;
+        }
+
+        let date: Date = row.date(date)?;
+        let change: i64 = row.amount(amount)?;
+        let mut metadata: Vec<(String, String)> = Vec::new();
+        if let Some(column) = id
+            && !row.get(column).is_empty()
+        {
+            metadata.push((REFERENCE.to_string(), row.get(column).to_string()));
+        }
+        if !row.get(memo).is_empty() {
+            metadata.push((MEMO.to_string(), row.get(memo).to_string()));
+        }
+        let Some(account) = accounts.get(row.get(institution)).map(ToString::to_string) else {
+            continue;
+        };
+        let category: &str = match mapping.category(row.get(major), row.get(sub)) {
+            Some(category) => category,
+            None if is_transfer => UNPAIRED_TRANSFERS,
+            None => uncategorized(change),
+        };
+        let transaction: Transaction =
+            record(date, row.get(content), change, &account, category, metadata);
+
+        if !is_transfer {
+            records.push((account, transaction));
+            continue;
+        }
+        let pair = transfers.iter().position(|(other, other_change, other_t)| {
+            *other != account && *other_change == -change && other_t.date == date
+        });
+        match pair {
+            Some(index) => {
+                let (other, _, other_transaction) = transfers.remove(index);
+                // Recorded once, in the statement of the account paying out
+                let (mut from, to) = match change < 0 {
+                    true => ((account, transaction), other),
+                    false => ((other, other_transaction), account),
+                };
+                from.1.postings[0].account = to;
+                records.push(from);
+            }
+            None => transfers.push((account, change, transaction)),
+        }
+    }
+    records.extend(
+        transfers
+            .into_iter()
+            .map(|(account, _, transaction)| (account, transaction)),
+    );
+
+    accounts.check()?;
+    Ok(AppStatements {
+        statements: statements(records),
+        skipped,
+    })
+}
+
+/// Groups `records` by account, each ordered by date
+fn statements(records: Vec<(String, Transaction)>) -> Vec<Statement> {
+    let mut statements: Vec<Statement> = Vec::new();
+    for (account, transaction) in records {
+        match statements.iter_mut().find(|s| s.account == account) {
+            Some(statement) => statement.transactions.push(transaction),
+            None => statements.push(Statement {
+                account,
+                transactions: vec![transaction],
+            }),
+        }
+    }
+    for statement in &mut statements {
+        statement.transactions.sort_by_key(|t| t.date);
+    }
+
+    statements
+}
+
+/// Kakei accounts of the accounts of an app, remembering those the mapping misses so all of them
+/// are reported at once
+struct Accounts<'a> {
+    mapping: &'a Mapping,
+    missing: BTreeSet<String>,
+}
+
+impl<'a> Accounts<'a> {
+    fn new(mapping: &'a Mapping) -> Self {
+        Self {
+            mapping,
+            missing: BTreeSet::new(),
+        }
+    }
+
+    fn get(&mut self, name: &str) -> Option<&'a str> {
+        let account = self.mapping.accounts.get(name).map(String::as_str);
+        if account.is_none() {
+            self.missing.insert(name.to_string());
+        }
+        account
+    }
+
+    fn check(self) -> Result<(), ImportError> {
+        if self.missing.is_empty() {
+            return Ok(());
+        }
+
+        let names: Vec<String> = self.missing.iter().map(|n| format!("\"{n}\"")).collect();
+        Err(ImportError::UnmappedAccounts(names.join(", ")))
+    }
+}
+
+/// The records of a CSV export under the names of their columns
+struct Table {
+    header: Vec<String>,
+    rows: Vec<Row>,
+}
+
+struct Row {
+    line: u64,
+    record: csv::StringRecord,
+}
+
+impl Table {
+    fn read(text: &str) -> Result<Self, ImportError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .trim(csv::Trim::All)
+            .flexible(true)
+            .from_reader(text.as_bytes());
+        let mut records = reader.records();
+
+        let header: Vec<String> = match records.next() {
+            Some(record) => record?.iter().map(ToString::to_string).collect(),
+            None => Vec::new(),
+        };
+        let mut rows: Vec<Row> = Vec::new();
+        for record in records {
+            let record = record?;
+            if record.iter().all(str::is_empty) {
+                continue;
+            }
+            rows.push(Row {
+                line: record.position().map_or(0, |p| p.line()),
+                record,
+            });
+        }
+
+        Ok(Self { header, rows })
+    }
+
+    /// The column of the first of `names` in the header
+    fn column(&self, names: &[&str]) -> Result<usize, ImportError> {
+        names
+            .iter()
+            .find_map(|name| self.header.iter().position(|h| h == name))
+            .ok_or_else(|| ImportError::Column(Column::Name(names[0].to_string())))
+    }
+}
+
+impl Row {
+    fn get(&self, column: usize) -> &str {
+        self.record.get(column).unwrap_or_default()
+    }
+
+    fn invalid(&self, message: String) -> ImportError {
+        ImportError::Invalid {
+            line: self.line,
+            message,
+        }
+    }
+
+    /// `2026/03/14` or `2026-03-14`
+    fn date(&self, column: usize) -> Result<Date, ImportError> {
+        let text: &str = self.get(column);
+        parse_date(&text.replace('-', "/"), "%Y/%m/%d")
+            .ok_or_else(|| self.invalid(format!("invalid date: \"{text}\"")))
+    }
+
+    fn amount(&self, column: usize) -> Result<i64, ImportError> {
+        let text: &str = self.get(column);
+        parse_number(text).ok_or_else(|| self.invalid(format!("invalid amount: \"{text}\"")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Mapping, UNPAIRED_TRANSFERS, read_moneyforward, read_zaim};
+    use crate::import::{ImportError, UNCATEGORIZED_EXPENSES};
+    use kakei_types::currency::{Amount, JPY};
+
+    #[test]
+    fn app_exports() -> anyhow::Result<()> {
+        let mapping = Mapping::parse(
+            r#"
+[categories]
+"食費/外食" = "Expenses:Food:EatingOut"
+"食費" = "Expenses:Food"
+"収入/給与" = "Income:Salary"
+"給与" = "Income:Salary"
+
+[accounts]
+"お財布" = "Assets:Wallet"
+"三井住友銀行" = "Assets:Bank"
+"楽天カード" = "Liabilities:Card"
+"#,
+        )?;
+
+        let zaim = "\
+日付,方法,カテゴリ,カテゴリの内訳,支払元,入金先,品目,メモ,お店,通貨,収入,支出,振替,残高調整,通貨変換前の金額,集計の設定
+2026-03-14,payment,食費,外食,お財布,-,牛丼,,松屋,JPY,0,980,0,0,980,常に集計に含める
+2026-03-15,payment,食費,食料品,楽天カード,-,,,ライフ,JPY,0,2400,0,0,2400,常に集計に含める
+2026-03-25,income,給与,-,-,三井住友銀行,,3月分,,JPY,300000,0,0,0,300000,常に集計に含める
+2026-03-26,transfer,-,-,三井住友銀行,お財布,,,,JPY,0,0,10000,0,10000,常に集計に含める
+2026-03-27,payment,日用雑貨,-,お財布,-,立替,,,JPY,0,500,0,0,500,集計に含めない
+2026-03-31,balance,-,-,-,お財布,,,,JPY,0,0,0,120,0,常に集計に含める
+";
+        let read = read_zaim(zaim, &mapping, false)?;
+        assert_eq!(read.skipped, 2);
+        let wallet = &read.statements[0];
+        assert_eq!(wallet.account, "Assets:Wallet");
+        assert_eq!(
+            wallet.transactions[0].to_string(),
+            "2026-03-14 \"松屋\"\n  ; item: 牛丼\n  Expenses:Food:EatingOut  980 JPY\n  Assets:Wallet\n"
+        );
+        assert_eq!(
+            read.statements[1].transactions[0].postings[0].account,
+            "Expenses:Food"
+        );
+        let bank = &read.statements[2];
+        assert_eq!(bank.transactions[0].postings[0].account, "Income:Salary");
+        assert_eq!(
+            bank.transactions[1].to_string(),
+            "2026-03-26 \"振替\"\n  Assets:Wallet  10000 JPY\n  Assets:Bank\n"
+        );
+        let all = read_zaim(zaim, &mapping, true)?;
+        assert_eq!(all.skipped, 1);
+        assert_eq!(
+            all.statements[0].transactions[1].postings[0].account,
+            UNCATEGORIZED_EXPENSES
+        );
+
+        let moneyforward = "\
+\"計算対象\",\"日付\",\"内容\",\"金額（円）\",\"保有金融機関\",\"大項目\",\"中項目\",\"メモ\",\"振替\",\"ID\"
+\"1\",\"2026/03/14\",\"松屋\",\"-980\",\"楽天カード\",\"食費\",\"外食\",\"\",\"0\",\"mf1\"
+\"0\",\"2026/03/20\",\"カード引き落とし\",\"-980\",\"三井住友銀行\",\"現金・カード\",\"カード引き落とし\",\"\",\"1\",\"mf2\"
+\"0\",\"2026/03/20\",\"カード引き落とし\",\"980\",\"楽天カード\",\"現金・カード\",\"カード引き落とし\",\"\",\"1\",\"mf3\"
+\"0\",\"2026/03/21\",\"ATM\",\"-5000\",\"三井住友銀行\",\"現金・カード\",\"ATM引き出し\",\"\",\"1\",\"mf4\"
+\"1\",\"2026/03/25\",\"給与\",\"300000\",\"三井住友銀行\",\"収入\",\"給与\",\"3月分\",\"0\",\"mf5\"
+\"0\",\"2026/03/26\",\"立替\",\"-500\",\"三井住友銀行\",\"食費\",\"外食\",\"\",\"0\",\"mf6\"
+";
+        let read = read_moneyforward(moneyforward, &mapping, false)?;
+        assert_eq!(read.skipped, 1);
+        let card = &read.statements[0];
+        assert_eq!(card.account, "Liabilities:Card");
+        assert_eq!(
+            card.transactions[0].to_string(),
+            "2026-03-14 \"松屋\"\n  ; ref: mf1\n  Expenses:Food:EatingOut  980 JPY\n  Liabilities:Card\n"
+        );
+        let bank = &read.statements[1];
+        assert_eq!(
+            bank.transactions[0].to_string(),
+            "2026-03-20 \"カード引き落とし\"\n  ; ref: mf2\n  Liabilities:Card  980 JPY\n  Assets:Bank\n"
+        );
+        assert_eq!(bank.transactions[1].postings[0].account, UNPAIRED_TRANSFERS);
+        assert_eq!(
+            bank.transactions[2].postings[0].amount,
+            Some(Amount::JPY(JPY(-300000)))
+        );
+        assert_eq!(bank.transactions.len(), 3);
+
+        let mut partial = mapping.clone();
+        partial.accounts.remove("楽天カード");
+        partial.accounts.remove("お財布");
+        assert!(matches!(
+            read_zaim(zaim, &partial, false),
+            Err(ImportError::UnmappedAccounts(names)) if names == "\"お財布\", \"楽天カード\""
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,320 @@
+//! ----- Beancount export -----
+//!
+//! Beancount is stricter than a kakeibo note: every account is opened before it is used and sits
+//! under one of five roots, account components start with a capital letter or a digit, commodities
+//! and metadata keys have their own alphabets, and strings are escaped. Names kakei accepts are
+//! rewritten to fit, so Fava and bean-check read the export as it is.
+//!
+//! A cost in kakei is the whole value paid or received, and capital gains come from kakei's own
+//! averaging instead of lots, so it is exported as a total price, `@@`, which balances the same.
+
+use crate::Processor;
+use kakei_types::{
+    currency::CurrencyCode,
+    date::Date,
+    entry::{Entry, Posting},
+};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Roots of Beancount accounts, under one of which every account is exported
+const ROOTS: [&str; 5] = ["Assets", "Liabilities", "Equity", "Income", "Expenses"];
+
+impl Processor {
+    /// The note as a Beancount ledger, ending with a `balance` assertion of every account on the
+    /// day after the last entry.
+    pub fn beancount(&self) -> String {
+        let mut text = String::from("option \"operating_currency\" \"JPY\"\n");
+        let Some(first) = self.entries.iter().find_map(Entry::date) else {
+            return text;
+        };
+
+        text.push('\n');
+        for (code, name) in [
+            (CurrencyCode::JPY, "Japanese yen"),
+            (CurrencyCode::SATS, "Satoshi, 1/100,000,000 BTC"),
+        ] {
+            let _ = writeln!(text, "{first} commodity {}", commodity(code));
+            let _ = writeln!(text, "  name: {}", string(name));
+        }
+
+        // Accounts written in `open` keep their date and currencies, the others open when first used
+        let mut opened: BTreeMap<String, (Date, Vec<CurrencyCode>)> = BTreeMap::new();
+        let mut open = |account: &str, date: Date, currencies: &[CurrencyCode]| {
+            let (opened, known) = opened
+                .entry(account_name(account))
+                .or_insert((date, Vec::new()));
+            *opened = (*opened).min(date);
+            for code in currencies {
+                if !known.contains(code) {
+                    known.push(*code);
+                }
+            }
+        };
+        for entry in &self.entries {
+            match entry {
+                Entry::Open(v) => open(&v.account, v.date, &v.currencies),
+                Entry::Budget(v) => open(&v.account, v.date, &[]),
+                Entry::Transaction(v) => {
+                    for posting in &v.postings {
+                        open(&posting.account, v.date, &[]);
+                    }
+                }
+                Entry::Include(_) | Entry::Price(_) | Entry::Recurring(_) => {}
+            }
+        }
+
+        let mut opens: Vec<(&String, &(Date, Vec<CurrencyCode>))> = opened.iter().collect();
+        opens.sort_by_key(|(account, (date, _))| (*date, *account));
+        text.push('\n');
+        for (account, (date, currencies)) in opens {
+            let _ = write!(text, "{date} open {account}");
+            let currencies: Vec<String> = currencies.iter().map(|c| commodity(*c)).collect();
+            if !currencies.is_empty() {
+                let _ = write!(text, " {}", currencies.join(","));
+            }
+            text.push('\n');
+        }
+
+        let mut balances: BTreeMap<(String, CurrencyCode), i64> = BTreeMap::new();
+        let mut last: Date = first;
+        for entry in &self.entries {
+            if let Some(date) = entry.date() {
+                last = last.max(date);
+            }
+            match entry {
+                Entry::Price(v) => {
+                    let _ = writeln!(
+                        text,
+                        "\n{} price {} {} {}",
+                        v.date,
+                        commodity(v.base),
+                        v.rate,
+                        commodity(v.quote)
+                    );
+                }
+                Entry::Budget(v) => {
+                    // The budget directive of Fava
+                    let _ = writeln!(
+                        text,
+                        "\n{} custom \"budget\" {} \"{}\" {} {}",
+                        v.date,
+                        account_name(&v.account),
+                        v.period,
+                        v.amount.value(),
+                        commodity(v.amount.code())
+                    );
+                }
+                Entry::Transaction(v) => {
+                    let flag = if v.generated { '!' } else { '*' };
+                    let _ = writeln!(text, "\n{} {flag} {} \"\"", v.date, string(&v.payee));
+                    for (key, value) in &v.metadata {
+                        let _ = writeln!(text, "  {}: {}", metadata_key(key), string(value));
+                    }
+                    for posting in &v.postings {
+                        let _ = writeln!(text, "  {}", self::posting(posting));
+                        if let Some(amount) = posting.amount {
+                            *balances
+                                .entry((account_name(&posting.account), amount.code()))
+                                .or_default() += amount.value();
+                        }
+                    }
+                }
+                Entry::Include(_) | Entry::Open(_) | Entry::Recurring(_) => {}
+            }
+        }
+
+        if !balances.is_empty() {
+            text.push('\n');
+        }
+        // Beancount checks the balance of an account with those of its sub-accounts
+        for (account, code) in balances.keys() {
+            let value: i64 = balances
+                .iter()
+                .filter(|((other, other_code), _)| {
+                    other_code == code
+                        && other
+                            .strip_prefix(account.as_str())
+                            .is_some_and(|rest| rest.is_empty() || rest.starts_with(':'))
+                })
+                .map(|(_, value)| value)
+                .sum();
+            let _ = writeln!(
+                text,
+                "{} balance {account} {value} {}",
+                last.add_days(1),
+                commodity(*code)
+            );
+        }
+
+        text
+    }
+}
+
+fn posting(posting: &Posting) -> String {
+    let account: String = account_name(&posting.account);
+    match (posting.amount, posting.cost) {
+        (Some(amount), Some(cost)) => format!(
+            "{account}  {} {} @@ {} {}",
+            amount.value(),
+            commodity(amount.code()),
+            cost.value().abs(),
+            commodity(cost.code())
+        ),
+        (Some(amount), None) => {
+            format!("{account}  {} {}", amount.value(), commodity(amount.code()))
+        }
+        (None, _) => account,
+    }
+}
+
+/// `expenses:eating_out` as `Expenses:Eating-out`, and an account under another root, such as
+/// `Opening:Balances`, under `Equity`.
+pub fn account_name(account: &str) -> String {
+    let mut components: Vec<String> = account
+        .split(':')
+        .map(|component| {
+            let component: String = component
+                .chars()
+                .map(|c| if c == '_' { '-' } else { c })
+                .collect();
+            let mut chars = component.chars();
+            match chars.next() {
+                Some(c) if c.is_ascii_lowercase() => {
+                    c.to_ascii_uppercase().to_string() + chars.as_str()
+                }
+                Some(c) if c.is_ascii_uppercase() || c.is_ascii_digit() || !c.is_ascii() => {
+                    component
+                }
+                _ => format!("X{component}"),
+            }
+        })
+        .collect();
+
+    if !ROOTS.contains(&components[0].as_str()) {
+        components.insert(0, "Equity".to_string());
+    }
+    components.join(":")
+}
+
+/// The commodity of `code`, capitals, digits and `'._-` of 2 to 24 characters, starting with a
+/// capital and ending with a capital or a digit. The codes kakei knows, `JPY` and `SATS`, are kept.
+pub fn commodity(code: CurrencyCode) -> String {
+    let mut name: String = code
+        .to_string()
+        .to_ascii_uppercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || "'._-".contains(*c))
+        .take(24)
+        .collect();
+    if !name.starts_with(|c: char| c.is_ascii_uppercase()) {
+        name.insert(0, 'C');
+    }
+    while name.ends_with(|c: char| !c.is_ascii_alphanumeric()) {
+        name.pop();
+    }
+    if name.len() < 2 {
+        name.push('X');
+    }
+
+    name
+}
+
+/// A metadata key starts with a lowercase letter followed by letters, digits, `-` and `_`
+fn metadata_key(key: &str) -> String {
+    let key: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    match key.chars().next() {
+        Some(c) if c.is_ascii_lowercase() => key,
+        Some(c) if c.is_ascii_uppercase() => c.to_ascii_lowercase().to_string() + &key[1..],
+        _ => format!("x{key}"),
+    }
+}
+
+fn string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::account_name;
+    use crate::Processor;
+    use kakei_parser::parse;
+
+    #[test]
+    fn export_beancount() -> anyhow::Result<()> {
+        let processor = Processor::from_entries(parse(
+            "\
+2026-01-01 open Assets:Bank JPY
+2026-03-01 budget Expenses:eating_out 30000 JPY
+
+2026-03-14 \"Matsuya\"
+  ; ref: 0001
+  Expenses:eating_out  980 JPY
+  Assets:Bank
+
+2026-03-14 price SATS 0.0145 JPY
+
+2026-03-15 \"Buy\"
+  Assets:Wallet  100000 SATS {1450 JPY}
+  Assets:Bank
+
+2026-03-15 \"Lunch\"
+  Expenses:eating_out:Lunch  750 JPY
+  Assets:Bank
+",
+        )?)?;
+
+        assert_eq!(
+            processor.beancount(),
+            "\
+option \"operating_currency\" \"JPY\"
+
+2026-01-01 commodity JPY
+  name: \"Japanese yen\"
+2026-01-01 commodity SATS
+  name: \"Satoshi, 1/100,000,000 BTC\"
+
+2026-01-01 open Assets:Bank JPY
+2026-03-01 open Expenses:Eating-out
+2026-03-15 open Assets:Wallet
+2026-03-15 open Expenses:Eating-out:Lunch
+
+2026-03-01 custom \"budget\" Expenses:Eating-out \"monthly\" 30000 JPY
+
+2026-03-14 * \"Matsuya\" \"\"
+  ref: \"0001\"
+  Expenses:Eating-out  980 JPY
+  Assets:Bank  -980 JPY
+
+2026-03-14 price SATS 0.0145 JPY
+
+2026-03-15 * \"Buy\" \"\"
+  Assets:Wallet  100000 SATS @@ 1450 JPY
+  Assets:Bank  -1450 JPY
+
+2026-03-15 * \"Lunch\" \"\"
+  Expenses:Eating-out:Lunch  750 JPY
+  Assets:Bank  -750 JPY
+
+2026-03-16 balance Assets:Bank -3180 JPY
+2026-03-16 balance Assets:Wallet 100000 SATS
+2026-03-16 balance Expenses:Eating-out 1730 JPY
+2026-03-16 balance Expenses:Eating-out:Lunch 750 JPY
+"
+        );
+
+        assert_eq!(account_name("Opening:balances"), "Equity:Opening:Balances");
+        assert_eq!(account_name("Assets:_cash"), "Assets:X-cash");
+        assert_eq!(account_name("Expenses:食費"), "Expenses:食費");
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+//! ----- Budget -----
+
+use crate::{Processor, is_sub_account};
+use kakei_types::{
+    currency::{Amount, CurrencyCode},
+    date::Date,
+    entry::{Budget, Entry},
+    query::{BudgetQuery, Percent},
+};
+use std::collections::BTreeMap;
+use tabled::{
+    Table,
+    settings::{Color, object::Rows},
+};
+
+impl Processor {
+    /// Budget against actual spending of every budgeted account, in the period containing `date`.
+    pub fn budgets(&self, date: Date) -> Vec<BudgetQuery> {
+        self.active_budgets(date)
+            .into_values()
+            .map(|budget| {
+                let range = budget.period.containing(budget.date, date);
+                let actual: i64 = self
+                    .transactions()
+                    .filter(|t| range.contains(&t.date))
+                    .flat_map(|t| &t.postings)
+                    .filter(|p| is_sub_account(&p.account, &budget.account))
+                    .filter_map(|p| p.amount)
+                    .filter(|a| a.code() == budget.amount.code())
+                    .map(|a| a.value())
+                    .sum();
+                let budgeted = budget.amount.value();
+
+                BudgetQuery {
+                    name: budget.account.clone(),
+                    from: range.start,
+                    to: range.end.add_days(-1),
+                    budgeted: budget.amount,
+                    actual: Amount::new(budget.amount.code(), actual),
+                    remaining: Amount::new(budget.amount.code(), budgeted - actual),
+                    used: Percent::of(actual, budgeted).unwrap_or(Percent(0.0)),
+                }
+            })
+            .collect()
+    }
+
+    /// The budget of each account and currency in effect on `date`. The latest budget of an
+    /// account in a currency wins.
+    pub fn active_budgets(&self, date: Date) -> BTreeMap<(&str, CurrencyCode), &Budget> {
+        let mut budgets: BTreeMap<(&str, CurrencyCode), &Budget> = BTreeMap::new();
+        for entry in &self.entries {
+            if let Entry::Budget(budget) = entry
+                && budget.date <= date
+            {
+                budgets.insert((&budget.account, budget.amount.code()), budget);
+            }
+        }
+
+        budgets
+    }
+
+    /// [`Processor::budgets`] as a table, with overruns in red when `color` is set.
+    pub fn budget_table(&self, date: Date, color: bool) -> Table {
+        let budgets = self.budgets(date);
+        let mut table = Table::new(&budgets);
+
+        for (index, budget) in budgets.iter().enumerate() {
+            if color && budget.is_over() {
+                // The first row is the header
+                table.modify(Rows::one(index + 1), Color::FG_RED);
+            }
+        }
+
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Processor;
+    use kakei_types::{
+        currency::{Amount, JPY, SATS},
+        date::Date,
+    };
+
+    #[test]
+    fn budget_against_actual() -> anyhow::Result<()> {
+        let src = "\
+2026-01 budget Expenses:Food 30000 JPY
+2026-04 budget Expenses:Food 60000 JPY
+2026-04-06 budget weekly Expenses:Cafe 1000 JPY
+2026-03-31 \"Supermarket\"
+  Expenses:Food:Groceries  5000 JPY
+  Assets:Wallet
+2026-04-10 \"Supermarket\"
+  Expenses:Food:Groceries  12000 JPY
+  Expenses:Cafe  1500 JPY
+  Expenses:Foodstuff  999 JPY
+  Assets:Wallet
+";
+        let processor = Processor::from_entries(kakei_parser::parse(src)?)?;
+        let budgets = processor.budgets(Date::new(2026, 4, 12)?);
+
+        assert_eq!(budgets.len(), 2);
+        assert_eq!(budgets[0].name, "Expenses:Cafe");
+        assert_eq!(budgets[0].from, Date::new(2026, 4, 6)?);
+        assert!(budgets[0].is_over());
+        assert_eq!(budgets[1].name, "Expenses:Food");
+        assert_eq!(budgets[1].actual, Amount::JPY(JPY(12000)));
+        assert_eq!(budgets[1].remaining, Amount::JPY(JPY(48000)));
+        assert_eq!(budgets[1].used.to_string(), "20.0%");
+        Ok(())
+    }
+
+    #[test]
+    fn budget_per_currency() -> anyhow::Result<()> {
+        let src = "\
+2026-04 budget Expenses:Food 1000 JPY
+2026-04 budget Expenses:Food 50000 SATS
+2026-04-10 \"Bitcoin cafe\"
+  Expenses:Food  60000 SATS
+  Assets:Wallet
+";
+        let processor = Processor::from_entries(kakei_parser::parse(src)?)?;
+        let date = Date::new(2026, 4, 12)?;
+        let budgets = processor.budgets(date);
+
+        assert_eq!(budgets.len(), 2);
+        assert_eq!(budgets[0].actual, Amount::JPY(JPY(0)));
+        assert_eq!(budgets[1].actual, Amount::SATS(SATS(60000)));
+        assert!(budgets[1].is_over());
+
+        assert!(
+            !processor
+                .budget_table(date, false)
+                .to_string()
+                .contains('\x1b')
+        );
+        assert!(
+            processor
+                .budget_table(date, true)
+                .to_string()
+                .contains('\x1b')
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+//! Parsed notes kept between reads
+//!
+//! A long-running command reads the same notes again and again. [`NoteCache`] keeps the source
+//! and parse result of every file it has read, and parses a file again only when its text has
+//! changed since the last read.
+
+use crate::{Processor, ProcessorError, encoding::Encoding, read_file};
+use kakei_parser::ParseError;
+use kakei_types::entry::Entry;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default)]
+pub struct NoteCache {
+    notes: HashMap<PathBuf, (String, Result<Vec<Entry>, ParseError>)>,
+
+    /// Encoding of every file, detected from each file when `None`
+    pub(crate) encoding: Option<Encoding>,
+}
+
+impl NoteCache {
+    pub fn new(encoding: Option<Encoding>) -> Self {
+        Self {
+            notes: HashMap::new(),
+            encoding,
+        }
+    }
+
+    /// Same as [`Processor::read`], parsing only the files changed since the last read.
+    pub fn read(&mut self, paths: Vec<PathBuf>) -> Result<Processor, ProcessorError> {
+        let mut entries: Vec<Entry> = Vec::new();
+        let mut files: Vec<PathBuf> = Vec::new();
+
+        for path in paths {
+            read_file(path, &mut entries, &mut files, self)?;
+        }
+
+        // Forget the files that are no longer included
+        self.notes.retain(|path, _| files.contains(path));
+
+        let mut processor = Processor::from_entries(entries)?;
+        processor.files = files;
+        Ok(processor)
+    }
+
+    /// Every file read so far, including the ones that failed to parse
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.notes.keys().map(PathBuf::as_path)
+    }
+
+    pub(crate) fn parse(&mut self, path: &Path, src: String) -> Result<Vec<Entry>, ProcessorError> {
+        let parsed = match self.notes.get(path) {
+            Some((cached, parsed)) if *cached == src => parsed.clone(),
+            _ => {
+                let parsed = kakei_parser::parse(&src);
+                self.notes.insert(path.to_path_buf(), (src, parsed.clone()));
+                parsed
+            }
+        };
+
+        parsed.map_err(|source| ProcessorError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NoteCache;
+
+    #[test]
+    fn reparse_changed_files() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("kakei-cache-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let note = dir.join("note.kakei");
+        let accounts = dir.join("accounts.kakei");
+        std::fs::write(&note, "include \"accounts.kakei\"\n")?;
+        std::fs::write(&accounts, "2026-01-01 open Assets:Wallet\n")?;
+
+        let mut cache = NoteCache::default();
+        assert!(cache.read(vec![note.clone()]).is_ok());
+        assert_eq!(cache.files().count(), 2);
+
+        std::fs::write(&accounts, "2026-01-01 open\n")?;
+        let error = cache.read(vec![note.clone()]).err().map(|e| e.to_string());
+        assert!(error.is_some_and(|e| e.starts_with(&accounts.display().to_string())));
+
+        std::fs::write(&note, "2026-01-01 open Assets:Bank\n")?;
+        let processor = cache.read(vec![note.clone()])?;
+        assert_eq!(processor.files, vec![note]);
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,293 @@
+//! ----- Charts drawn with block characters -----
+//!
+//! Charts are plain text fitted to a width, so they print wherever a table does. Only JPY is
+//! charted, like the other reports on spending.
+
+use crate::{Processor, is_asset_account, root_account};
+use kakei_types::{
+    currency::{Amount, JPY},
+    date::Date,
+};
+use std::collections::BTreeMap;
+use std::ops::Range;
+use thiserror::Error;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ChartKind {
+    /// Spending of a month by category
+    Spending,
+
+    /// Income and expenses month by month
+    Monthly,
+
+    /// A sparkline of each asset balance
+    Balances,
+}
+
+#[derive(Debug, Error)]
+#[error("unknown chart: \"{0}\", expected spending, monthly or balances")]
+pub struct UnknownChartKind(String);
+
+impl std::fmt::Display for ChartKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Spending => write!(f, "spending"),
+            Self::Monthly => write!(f, "monthly"),
+            Self::Balances => write!(f, "balances"),
+        }
+    }
+}
+
+impl std::str::FromStr for ChartKind {
+    type Err = UnknownChartKind;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "spending" => Ok(Self::Spending),
+            "monthly" => Ok(Self::Monthly),
+            "balances" => Ok(Self::Balances),
+            v => Err(UnknownChartKind(v.to_string())),
+        }
+    }
+}
+
+/// Income and expenses of one month in JPY
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct MonthlyTotal {
+    /// First day of the month
+    pub month: Date,
+    pub income: i64,
+    pub expenses: i64,
+}
+
+impl Processor {
+    /// Spending in `range` by category, such as `Expenses:Food` for `Expenses:Food:Lunch`,
+    /// largest first.
+    pub fn spending_by_category(&self, range: Range<Date>) -> Vec<(String, i64)> {
+        let mut categories: BTreeMap<String, i64> = BTreeMap::new();
+        for (account, value) in self.jpy_postings(range) {
+            if root_account(account) == "Expenses" {
+                let category: Vec<&str> = account.split(':').take(2).collect();
+                *categories.entry(category.join(":")).or_default() += value;
+            }
+        }
+
+        let mut spending: Vec<(String, i64)> = categories.into_iter().collect();
+        spending.sort_by(|a, b| b.1.cmp(&a.1));
+        spending
+    }
+
+    /// Income and expenses of each month from the month of `months.start`
+    pub fn monthly_totals(&self, months: Range<Date>) -> Vec<MonthlyTotal> {
+        let mut totals: Vec<MonthlyTotal> = Vec::new();
+        let mut month: Date = months.start;
+        while month < months.end {
+            let next: Date = month.add_months(1);
+            let mut total = MonthlyTotal {
+                month,
+                income: 0,
+                expenses: 0,
+            };
+            for (account, value) in self.jpy_postings(month..next) {
+                match root_account(account) {
+                    "Income" => total.income -= value,
+                    "Expenses" => total.expenses += value,
+                    _ => {}
+                }
+            }
+
+            totals.push(total);
+            month = next;
+        }
+
+        totals
+    }
+
+    pub fn spending_chart(&self, range: Range<Date>, width: usize) -> String {
+        bar_chart(&self.spending_by_category(range), width)
+    }
+
+    pub fn monthly_chart(&self, months: Range<Date>, width: usize) -> String {
+        let bars: Vec<(String, i64)> = self
+            .monthly_totals(months)
+            .into_iter()
+            .flat_map(|total| {
+                [
+                    (
+                        format!(
+                            "{:04}-{:02} income",
+                            total.month.year(),
+                            total.month.month()
+                        ),
+                        total.income,
+                    ),
+                    ("        expenses".to_string(), total.expenses),
+                ]
+            })
+            .collect();
+        bar_chart(&bars, width)
+    }
+
+    /// One sparkline for each `Assets` account holding JPY, with its balance sampled evenly
+    /// over `range`.
+    pub fn balance_chart(&self, range: Range<Date>, width: usize) -> String {
+        let mut postings: BTreeMap<&str, Vec<(Date, i64)>> = BTreeMap::new();
+        for transaction in self.transactions().filter(|t| t.date < range.end) {
+            for posting in &transaction.postings {
+                if let Some(Amount::JPY(JPY(value))) = posting.amount
+                    && is_asset_account(&posting.account)
+                {
+                    let account = postings.entry(&posting.account).or_default();
+                    account.push((transaction.date, value));
+                }
+            }
+        }
+
+        let (label_width, value_width) = widths(
+            postings
+                .iter()
+                .map(|(account, postings)| (*account, postings.iter().map(|(_, v)| v).sum())),
+        );
+        let points: usize = width.saturating_sub(label_width + value_width + 2).max(1);
+
+        // The end of each of `points` equal steps through the range
+        let days: i64 = range.end.to_days() - range.start.to_days();
+        let samples: Vec<Date> = (1..=points as i64)
+            .map(|i| range.start.add_days(days * i / points as i64))
+            .collect();
+
+        let mut chart = String::new();
+        for (account, postings) in &postings {
+            let line: Vec<i64> = samples
+                .iter()
+                .map(|sample| {
+                    postings
+                        .iter()
+                        .filter(|(date, _)| date < sample)
+                        .map(|(_, value)| value)
+                        .sum()
+                })
+                .collect();
+            let balance = JPY(line.last().copied().unwrap_or_default()).to_string();
+            chart.push_str(&format!(
+                "{account:<label_width$} {} {balance:>value_width$}\n",
+                sparkline(&line)
+            ));
+        }
+
+        chart
+    }
+
+    /// Account and value of every JPY posting in `range`
+    fn jpy_postings(&self, range: Range<Date>) -> impl Iterator<Item = (&str, i64)> {
+        self.transactions()
+            .filter(move |t| range.contains(&t.date))
+            .flat_map(|t| &t.postings)
+            .filter_map(|p| match p.amount {
+                Some(Amount::JPY(JPY(value))) => Some((p.account.as_str(), value)),
+                _ => None,
+            })
+    }
+}
+
+/// One labelled bar for each of `bars`, scaled to the largest one
+fn bar_chart(bars: &[(String, i64)], width: usize) -> String {
+    let (label_width, value_width) =
+        widths(bars.iter().map(|(label, value)| (label.as_str(), *value)));
+    let bar_width: usize = width.saturating_sub(label_width + value_width + 2).max(1);
+    let max: i64 = bars
+        .iter()
+        .map(|(_, value)| *value)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    bars.iter()
+        .map(|(label, value)| {
+            let value_text = JPY(*value).to_string();
+            format!(
+                "{label:<label_width$} {:<bar_width$} {value_text:>value_width$}\n",
+                bar(*value, max, bar_width)
+            )
+        })
+        .collect()
+}
+
+/// Widths of the label and value columns
+fn widths<'a>(rows: impl Iterator<Item = (&'a str, i64)>) -> (usize, usize) {
+    rows.fold((0, 0), |(label, value), row| {
+        (
+            label.max(row.0.chars().count()),
+            value.max(JPY(row.1).to_string().len()),
+        )
+    })
+}
+
+/// A bar `width * value / max` characters long, in eighths of a character
+fn bar(value: i64, max: i64, width: usize) -> String {
+    const EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+    let eighths = (value.max(0) as i128 * width as i128 * 8 / max as i128) as usize;
+    let mut bar: String = "█".repeat(eighths / 8);
+    if !eighths.is_multiple_of(8) {
+        bar.push(EIGHTHS[eighths % 8]);
+    }
+    bar
+}
+
+/// `values` scaled between their lowest and highest
+fn sparkline(values: &[i64]) -> String {
+    const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let min: i64 = values.iter().copied().min().unwrap_or_default();
+    let max: i64 = values.iter().copied().max().unwrap_or_default();
+    values
+        .iter()
+        .map(|value| match max - min {
+            0 => LEVELS[3],
+            range => LEVELS[((value - min) as i128 * 7 / range as i128) as usize],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bar, sparkline};
+    use crate::Processor;
+    use kakei_types::date::Date;
+
+    #[test]
+    fn charts() -> anyhow::Result<()> {
+        assert_eq!(bar(5, 10, 3), "█▌");
+        assert_eq!(bar(-5, 10, 3), "");
+        assert_eq!(sparkline(&[0, 70, 35, 70]), "▁█▄█");
+        assert_eq!(sparkline(&[3, 3]), "▄▄");
+
+        let src = r#"
+2026-03-01 "Matsuya"
+  Expenses:Food:Lunch  1200 JPY
+  Assets:Wallet
+
+2026-03-02 "Ito-Yokado"
+  Expenses:Food        2400 JPY
+  Assets:Wallet
+
+2026-03-05 "JR"
+  Expenses:Transport   1800 JPY
+  Assets:Wallet
+"#;
+        let processor = Processor::from_entries(kakei_parser::parse(src)?)?;
+        let march = Date::new(2026, 3, 1)?..Date::new(2026, 4, 1)?;
+
+        assert_eq!(
+            processor.spending_chart(march.clone(), 34),
+            "Expenses:Food      ██████ 3600 JPY\n\
+             Expenses:Transport ███    1800 JPY\n"
+        );
+        assert_eq!(
+            processor.balance_chart(march, 28),
+            "Assets:Wallet ▄▄▄▄ -5400 JPY\n"
+        );
+        Ok(())
+    }
+}
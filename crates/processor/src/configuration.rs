@@ -1,6 +1,81 @@
 //! ----- Configuration -----
 
-use tabled::Tabled;
+use crate::import::CsvProfile;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
-#[derive(Debug, Tabled)]
-pub struct Configuration {}
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Configuration {
+    /// Amount to save each month in JPY, used by `kakei reflect`
+    pub savings_goal: Option<i64>,
+
+    /// Accounts of each kakeibo category
+    pub kakeibo: KakeiboConfiguration,
+
+    /// Statements read by `kakei import`
+    pub import: ImportConfiguration,
+}
+
+/// Accounts counted in each category of the kakeibo method. Sub-accounts are included.
+///
+/// ```toml
+/// [kakeibo]
+/// fixed = ["Expenses:Rent", "Expenses:Utilities"]
+/// needs = ["Expenses:Food"]
+/// wants = ["Expenses:Hobby"]
+/// culture = ["Expenses:Books"]
+/// unexpected = ["Expenses:Medical"]
+/// ```
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KakeiboConfiguration {
+    /// Fixed costs, such as rent
+    pub fixed: Vec<String>,
+
+    /// 生活費, survival
+    pub needs: Vec<String>,
+
+    /// 娯楽, optional spending
+    pub wants: Vec<String>,
+
+    /// 文化, culture
+    pub culture: Vec<String>,
+
+    /// 特別出費, unexpected
+    pub unexpected: Vec<String>,
+}
+
+/// Statement layouts and payee rules of `kakei import`
+///
+/// ```toml
+/// [import]
+/// rules = "/home/me/.config/kakei/rules.toml"
+/// mapping = "/home/me/.config/kakei/zaim.toml"
+///
+/// [import.profiles.mizuho]
+/// account = "Assets:Bank:Mizuho"
+/// date = "取引日"
+/// payee = "摘要"
+/// amount = "金額"
+///
+/// [import.accounts]
+/// "4980-0000" = "Liabilities:Card:Amex"
+/// ```
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportConfiguration {
+    /// File of payee rules, see [`crate::import::Rules`]
+    pub rules: Option<PathBuf>,
+
+    /// File of the categories and accounts of Zaim and MoneyForward ME, see
+    /// [`crate::apps::Mapping`]
+    pub mapping: Option<PathBuf>,
+
+    /// CSV layouts by name, see [`CsvProfile`]
+    pub profiles: BTreeMap<String, CsvProfile>,
+
+    /// Kakei accounts of the account IDs in OFX statements
+    pub accounts: BTreeMap<String, String>,
+}
@@ -0,0 +1,272 @@
+//! ----- Duplicate imports -----
+//!
+//! A statement imported twice, or overlapping the one imported last month, would count the same
+//! spending again. A [`Fingerprint`] identifies one statement line among the transactions already
+//! in the note.
+//!
+//! A card often posts a purchase a few days after the day written in the note, so the same amount
+//! within [`WINDOW`] days is a [`Duplicate::Possible`] for the user to review, while only the same
+//! ID, or the same date, amount and payee, is [`Duplicate::Certain`].
+
+use crate::{Processor, balance, import::REFERENCE, ofx::FITID};
+use kakei_types::{currency::Amount, date::Date, entry::Transaction};
+use unicode_normalization::UnicodeNormalization;
+
+/// Metadata keys of the IDs banks give statement lines
+const REFERENCES: [&str; 2] = [REFERENCE, FITID];
+
+/// Days a possible duplicate may be apart
+pub const WINDOW: i64 = 3;
+
+/// The transaction in the note an imported one duplicates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Duplicate<'a> {
+    /// The same ID, or the same date, amount and payee
+    Certain(&'a Transaction),
+
+    /// The same amount within [`WINDOW`] days, which may be another purchase
+    Possible(&'a Transaction),
+}
+
+/// What identifies a transaction as one line of the statement of `account`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fingerprint {
+    pub date: Date,
+    pub account: String,
+
+    /// Change of `account`
+    pub amount: Amount,
+
+    /// Payee in NFKC lowercase without spaces, as banks and people spell it differently, such as
+    /// `ﾏﾂﾔ` and `マツヤ` or `ＡＴＭ` and `atm`
+    pub payee: String,
+
+    /// IDs of the bank, from the `ref` and `fitid` metadata
+    pub references: Vec<(String, String)>,
+}
+
+impl Fingerprint {
+    /// `None` when `transaction` does not post to `account`, or cannot be balanced.
+    pub fn new(transaction: &Transaction, account: &str) -> Option<Self> {
+        // The statement account of an imported transaction has its amount left out
+        let mut transaction: Transaction = transaction.clone();
+        balance(&mut transaction).ok()?;
+
+        let mut amounts = transaction
+            .postings
+            .iter()
+            .filter(|p| p.account == account)
+            .filter_map(|p| p.amount);
+        let first: Amount = amounts.next()?;
+        let rest: i64 = amounts
+            .filter(|amount| amount.code() == first.code())
+            .map(|amount| amount.value())
+            .sum();
+
+        Some(Self {
+            date: transaction.date,
+            account: account.to_string(),
+            amount: Amount::new(first.code(), first.value() + rest),
+            payee: transaction
+                .payee
+                .nfkc()
+                .flat_map(char::to_lowercase)
+                .filter(|c| !c.is_whitespace())
+                .collect(),
+            references: transaction
+                .metadata
+                .iter()
+                .filter(|(key, _)| REFERENCES.contains(&key.as_str()))
+                .cloned()
+                .collect(),
+        })
+    }
+
+    /// The same ID when both have one of the same kind, or else the same date, amount and payee
+    pub fn matches(&self, other: &Self) -> bool {
+        if self.account != other.account {
+            return false;
+        }
+
+        match self.same_reference(other) {
+            Some(same) => same,
+            None => {
+                self.date == other.date && self.amount == other.amount && self.payee == other.payee
+            }
+        }
+    }
+
+    /// The same amount within [`WINDOW`] days, unless both have IDs of the same kind
+    pub fn resembles(&self, other: &Self) -> bool {
+        self.account == other.account
+            && self.same_reference(other).is_none()
+            && self.amount == other.amount
+            && (self.date.to_days() - other.date.to_days()).abs() <= WINDOW
+    }
+
+    /// Whether the IDs are the same, when both have one of the same kind
+    fn same_reference(&self, other: &Self) -> Option<bool> {
+        self.references.iter().find_map(|(key, value)| {
+            other
+                .references
+                .iter()
+                .find(|(other, _)| other == key)
+                .map(|(_, other)| other == value)
+        })
+    }
+}
+
+impl Processor {
+    /// The transaction already in the note each of `imported` may duplicate, reading them as
+    /// lines of the statement of `account`.
+    ///
+    /// A transaction in the note is matched at most once, so two coffees bought on the same day
+    /// are both kept when the note holds one of them. Certain duplicates are matched first, and a
+    /// possible one is the closest in date.
+    pub fn duplicates(
+        &self,
+        imported: &[Transaction],
+        account: &str,
+    ) -> Vec<Option<Duplicate<'_>>> {
+        let mut existing: Vec<(&Transaction, Fingerprint)> = self
+            .transactions()
+            .filter_map(|t| Fingerprint::new(t, account).map(|fingerprint| (t, fingerprint)))
+            .collect();
+        let fingerprints: Vec<Option<Fingerprint>> = imported
+            .iter()
+            .map(|transaction| Fingerprint::new(transaction, account))
+            .collect();
+
+        let mut duplicates: Vec<Option<Duplicate>> = fingerprints
+            .iter()
+            .map(|fingerprint| {
+                let fingerprint = fingerprint.as_ref()?;
+                let index = existing
+                    .iter()
+                    .position(|(_, other)| fingerprint.matches(other))?;
+                Some(Duplicate::Certain(existing.remove(index).0))
+            })
+            .collect();
+        for (duplicate, fingerprint) in duplicates.iter_mut().zip(&fingerprints) {
+            let Some(fingerprint) = fingerprint.as_ref().filter(|_| duplicate.is_none()) else {
+                continue;
+            };
+            let closest = existing
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, other))| fingerprint.resembles(other))
+                .min_by_key(|(_, (_, other))| {
+                    (fingerprint.date.to_days() - other.date.to_days()).abs()
+                })
+                .map(|(index, _)| index);
+            if let Some(index) = closest {
+                *duplicate = Some(Duplicate::Possible(existing.remove(index).0));
+            }
+        }
+
+        duplicates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Duplicate;
+    use crate::Processor;
+    use kakei_parser::parse;
+    use kakei_types::entry::{Entry, Transaction};
+
+    #[test]
+    fn find_duplicates() -> anyhow::Result<()> {
+        let processor = Processor::from_entries(parse(
+            "\
+2026-03-01 \"Matsuya Shibuya\"
+  Expenses:Food  980 JPY
+  Assets:Bank
+
+2026-03-02 \"ATM\"
+  ; ref: 0002
+  Assets:Wallet  10000 JPY
+  Assets:Bank
+
+2026-03-05 \"ﾏﾂﾔ\"
+  Expenses:Food  640 JPY
+  Assets:Bank
+
+2026-03-10 \"Amazon\"
+  Expenses:Books  1500 JPY
+  Assets:Bank
+
+2026-03-20 \"Sukiya\"
+  Expenses:Food  500 JPY
+  Assets:Bank
+",
+        )?)?;
+        let imported: Vec<Transaction> = parse(
+            "\
+2026-03-01 \"MATSUYA SHIBUYA\"
+  Expenses:Uncategorized  980 JPY
+  Assets:Bank
+
+2026-03-01 \"MATSUYA SHIBUYA\"
+  Expenses:Uncategorized  980 JPY
+  Assets:Bank
+
+2026-03-03 \"ATM SHIBUYA\"
+  ; ref: 0002
+  Expenses:Uncategorized  10000 JPY
+  Assets:Bank
+
+2026-03-03 \"ATM\"
+  ; ref: 0003
+  Expenses:Uncategorized  10000 JPY
+  Assets:Bank
+
+2026-03-05 \"マツヤ\"
+  Expenses:Uncategorized  640 JPY
+  Assets:Bank
+
+2026-03-12 \"AMAZON.CO.JP\"
+  Expenses:Uncategorized  1500 JPY
+  Assets:Bank
+
+2026-03-24 \"SUKIYA\"
+  Expenses:Uncategorized  500 JPY
+  Assets:Bank
+",
+        )?
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Entry::Transaction(transaction) => Some(transaction),
+            _ => None,
+        })
+        .collect();
+
+        let duplicates: Vec<Option<(bool, &str)>> = processor
+            .duplicates(&imported, "Assets:Bank")
+            .into_iter()
+            .map(|duplicate| match duplicate? {
+                Duplicate::Certain(t) => Some((true, t.payee.as_str())),
+                Duplicate::Possible(t) => Some((false, t.payee.as_str())),
+            })
+            .collect();
+        assert_eq!(
+            duplicates,
+            vec![
+                Some((true, "Matsuya Shibuya")),
+                None,
+                Some((true, "ATM")),
+                None,
+                Some((true, "ﾏﾂﾔ")),
+                Some((false, "Amazon")),
+                None,
+            ]
+        );
+        assert!(
+            processor
+                .duplicates(&imported, "Assets:Wallet")
+                .iter()
+                .all(Option::is_none)
+        );
+        Ok(())
+    }
+}
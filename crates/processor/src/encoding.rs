@@ -0,0 +1,199 @@
+//! ----- Decoding files -----
+//!
+//! Japanese banks and card companies export statements in Shift_JIS, and older tools write
+//! EUC-JP. [`decode`] reads any of them, and UTF-8 with or without a BOM, as text, and
+//! [`Encoding::encode`] writes the text back as it was.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Text encoding of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Encoding {
+    #[serde(rename = "utf-8", alias = "utf8")]
+    Utf8,
+
+    /// Shift_JIS with the Windows extensions (CP932), as Excel saves it
+    #[serde(rename = "shift_jis", alias = "sjis", alias = "cp932")]
+    ShiftJis,
+
+    #[serde(rename = "euc-jp", alias = "eucjp")]
+    EucJp,
+}
+
+#[derive(Debug, Error)]
+#[error("unknown encoding: \"{0}\", expected utf-8, shift_jis (sjis, cp932) or euc-jp")]
+pub struct UnknownEncoding(String);
+
+#[derive(Debug, Clone, Error)]
+pub enum DecodeError {
+    #[error("the file is not valid {0}")]
+    Invalid(Encoding),
+
+    #[error("the file is not UTF-8, Shift_JIS or EUC-JP")]
+    Unknown,
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Utf8 => write!(f, "utf-8"),
+            Self::ShiftJis => write!(f, "shift_jis"),
+            Self::EucJp => write!(f, "euc-jp"),
+        }
+    }
+}
+
+impl std::str::FromStr for Encoding {
+    type Err = UnknownEncoding;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "utf-8" | "utf8" => Ok(Self::Utf8),
+            "shift_jis" | "shift-jis" | "sjis" | "cp932" | "windows-31j" => Ok(Self::ShiftJis),
+            "euc-jp" | "eucjp" => Ok(Self::EucJp),
+            _ => Err(UnknownEncoding(s.to_string())),
+        }
+    }
+}
+
+impl Encoding {
+    fn decoder(self) -> &'static encoding_rs::Encoding {
+        match self {
+            Self::Utf8 => encoding_rs::UTF_8,
+            // The WHATWG Shift_JIS is CP932, with NEC and IBM extensions such as ① and ㈱
+            Self::ShiftJis => encoding_rs::SHIFT_JIS,
+            Self::EucJp => encoding_rs::EUC_JP,
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> Option<String> {
+        self.decoder()
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .map(|text| text.into_owned())
+    }
+
+    /// `text` in the encoding, or `None` if it has a character the encoding cannot write
+    pub fn encode(self, text: &str) -> Option<Vec<u8>> {
+        let (bytes, _, unmappable) = self.decoder().encode(text);
+        (!unmappable).then(|| bytes.into_owned())
+    }
+}
+
+/// Decodes `bytes` as `encoding`, or else as the first of UTF-8, Shift_JIS and EUC-JP they are
+/// valid in. A UTF-8 BOM is dropped either way.
+pub fn decode(bytes: &[u8], encoding: Option<Encoding>) -> Result<String, DecodeError> {
+    detect(bytes, encoding).map(|(text, _)| text)
+}
+
+/// Same as [`decode`], also returning the encoding the text was read in.
+pub fn detect(bytes: &[u8], encoding: Option<Encoding>) -> Result<(String, Encoding), DecodeError> {
+    let (bytes, encoding) = match bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        Some(rest) => (rest, encoding.or(Some(Encoding::Utf8))),
+        None => (bytes, encoding),
+    };
+    if let Some(encoding) = encoding {
+        return encoding
+            .decode(bytes)
+            .map(|text| (text, encoding))
+            .ok_or(DecodeError::Invalid(encoding));
+    }
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Ok((text.to_string(), Encoding::Utf8));
+    }
+
+    match (
+        Encoding::ShiftJis.decode(bytes),
+        Encoding::EucJp.decode(bytes),
+    ) {
+        // Kana and kanji written in EUC-JP are often valid Shift_JIS too, but read as half-width
+        // katakana among kanji and user-defined characters
+        (Some(shift_jis), Some(euc_jp)) if likelihood(&euc_jp) > likelihood(&shift_jis) => {
+            Ok((euc_jp, Encoding::EucJp))
+        }
+        (Some(text), _) => Ok((text, Encoding::ShiftJis)),
+        (None, Some(text)) => Ok((text, Encoding::EucJp)),
+        (None, None) => Err(DecodeError::Unknown),
+    }
+}
+
+/// Number of hiragana and full-width katakana in `text`, less the characters hardly written in a
+/// statement: those of the private use area, and half-width katakana next to a full-width
+/// character. Half-width katakana themselves are common in Shift_JIS statements, as in `ﾐﾂｲｽﾐﾄﾓ`.
+fn likelihood(text: &str) -> isize {
+    let chars: Vec<char> = text.chars().collect();
+    let half_width = |c: char| ('\u{FF61}'..='\u{FF9F}').contains(&c);
+    let full_width = |c: char| !c.is_ascii() && !half_width(c);
+
+    let kana = chars
+        .iter()
+        .filter(|c| ('\u{3041}'..='\u{30FF}').contains(*c))
+        .count();
+    let private = chars
+        .iter()
+        .filter(|c| ('\u{E000}'..='\u{F8FF}').contains(*c))
+        .count();
+    let mixed = chars
+        .windows(2)
+        .filter(|pair| {
+            (half_width(pair[0]) && full_width(pair[1]))
+                || (full_width(pair[0]) && half_width(pair[1]))
+        })
+        .count();
+
+    kana as isize - private as isize - mixed as isize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DecodeError, Encoding, decode, detect};
+
+    #[test]
+    fn detect_encodings() -> anyhow::Result<()> {
+        let text = "2026/03/01,セブンイレブン 渋谷店,1200,①\n";
+        let (shift_jis, _, _) = encoding_rs::SHIFT_JIS.encode(text);
+        let (euc_jp, _, _) = encoding_rs::EUC_JP.encode("2026/03/01,まつや 渋谷店,980\n");
+        let kanji = "2026/03/01,三井住友銀行,振込,1200\n";
+        let half_width = "2026/03/01,ﾐﾂｲｽﾐﾄﾓｷﾞﾝｺｳ,980\n";
+
+        assert_eq!(decode(text.as_bytes(), None)?, text);
+        assert_eq!(
+            decode(&[b"\xEF\xBB\xBF", text.as_bytes()].concat(), None)?,
+            text
+        );
+        assert_eq!(decode(&shift_jis, None)?, text);
+        assert_eq!(decode(&euc_jp, None)?, "2026/03/01,まつや 渋谷店,980\n");
+        // Also valid Shift_JIS, as `ｻｰｰ貎ｻﾍｧｶ荵ﾔ,ｿｶｹ` and a user-defined character
+        assert_eq!(decode(&encoding_rs::EUC_JP.encode(kanji).0, None)?, kanji);
+        assert_eq!(
+            decode(&encoding_rs::EUC_JP.encode("三菱ＵＦＪ銀行").0, None)?,
+            "三菱ＵＦＪ銀行"
+        );
+        // Also valid EUC-JP, as `仟化伉啖渺些`
+        assert_eq!(
+            decode(&encoding_rs::SHIFT_JIS.encode(half_width).0, None)?,
+            half_width
+        );
+
+        assert_eq!(decode(&shift_jis, Some(Encoding::ShiftJis))?, text);
+        assert_eq!(detect(&shift_jis, None)?.1, Encoding::ShiftJis);
+        assert_eq!(detect(&euc_jp, None)?.1, Encoding::EucJp);
+        assert_eq!(
+            Encoding::ShiftJis.encode(text).as_deref(),
+            Some(&shift_jis[..])
+        );
+        assert_eq!(Encoding::EucJp.encode("🍚"), None);
+        assert!(matches!(
+            decode(&shift_jis, Some(Encoding::Utf8)),
+            Err(DecodeError::Invalid(Encoding::Utf8))
+        ));
+        assert!(matches!(
+            decode(b"\x82\xFF", None),
+            Err(DecodeError::Unknown)
+        ));
+
+        assert_eq!("CP932".parse::<Encoding>()?, Encoding::ShiftJis);
+        assert!("latin1".parse::<Encoding>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,263 @@
+//! ----- Cash-flow forecast -----
+//!
+//! Asset balances in JPY are projected month by month with the recurring transactions and the
+//! budgets. A budget is spent from the asset account which paid for its account most often,
+//! unless a recurring transaction already posts to it.
+
+use crate::{Processor, ProcessorError, is_asset_account, is_sub_account};
+use kakei_types::{
+    currency::{CurrencyCode, JPY},
+    date::Date,
+    entry::Transaction,
+};
+use std::collections::BTreeMap;
+use tabled::{Table, builder::Builder, settings::Color};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Forecast {
+    /// Asset accounts, in the order of [`ForecastMonth::balances`]
+    pub accounts: Vec<String>,
+    pub months: Vec<ForecastMonth>,
+}
+
+/// Balances at the end of one month
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForecastMonth {
+    /// First day of the month
+    pub month: Date,
+    pub balances: Vec<JPY>,
+}
+
+impl Forecast {
+    /// The first month each account would go negative in
+    pub fn first_negative(&self) -> Vec<(String, Date)> {
+        self.accounts
+            .iter()
+            .enumerate()
+            .filter_map(|(i, account)| {
+                self.months
+                    .iter()
+                    .find(|m| m.balances[i].0 < 0)
+                    .map(|m| (account.clone(), m.month))
+            })
+            .collect()
+    }
+
+    /// One row per month, with negative balances in red when `color` is set.
+    pub fn table(&self, color: bool) -> Table {
+        let mut builder = Builder::default();
+        builder.push_record(std::iter::once("month".to_string()).chain(self.accounts.clone()));
+        for month in &self.months {
+            let label = format!("{:04}-{:02}", month.month.year(), month.month.month());
+            builder.push_record(
+                std::iter::once(label).chain(month.balances.iter().map(ToString::to_string)),
+            );
+        }
+
+        let mut table = builder.build();
+        for (row, month) in self.months.iter().enumerate() {
+            for (column, balance) in month.balances.iter().enumerate() {
+                if color && balance.0 < 0 {
+                    // The first row is the header, the first column is the month
+                    table.modify((row + 1, column + 1), Color::FG_RED);
+                }
+            }
+        }
+
+        table
+    }
+}
+
+impl Processor {
+    /// Projects asset balances from `today` until the month containing `until`.
+    pub fn forecast(&self, today: Date, until: Date) -> Result<Forecast, ProcessorError> {
+        let first_month = first_day(today);
+        let end = first_day(until).add_months(1);
+
+        let mut balances: BTreeMap<String, i64> = self
+            .until(today)?
+            .jpy_queries
+            .iter()
+            .filter(|q| is_asset_account(&q.name))
+            .map(|q| (q.name.clone(), q.total.0))
+            .collect();
+
+        let generated: Vec<Transaction> = self.generate(today.add_days(1)..end)?;
+        let recurring_accounts: Vec<&str> = self
+            .recurring()
+            .flat_map(|r| &r.postings)
+            .map(|p| p.account.as_str())
+            .collect();
+
+        let mut monthly: Vec<(Date, BTreeMap<String, i64>)> = Vec::new();
+        let mut month = first_month;
+        while month < end {
+            let next = month.add_months(1);
+
+            for transaction in generated
+                .iter()
+                .filter(|t| month <= t.date && t.date < next)
+            {
+                for posting in &transaction.postings {
+                    if let Some(weight) = posting.weight()
+                        && weight.code() == CurrencyCode::JPY
+                        && is_asset_account(&posting.account)
+                    {
+                        *balances.entry(posting.account.clone()).or_default() += weight.value();
+                    }
+                }
+            }
+
+            for (account, spending) in self.budgeted_spending(today, month..next) {
+                let covered = recurring_accounts
+                    .iter()
+                    .any(|r| is_sub_account(r, account));
+                if !covered && let Some(funding) = self.funding_account(account) {
+                    *balances.entry(funding).or_default() -= spending;
+                }
+            }
+
+            monthly.push((month, balances.clone()));
+            month = next;
+        }
+
+        let accounts: Vec<String> = balances.keys().cloned().collect();
+        let months = monthly
+            .into_iter()
+            .map(|(month, balances)| ForecastMonth {
+                month,
+                balances: accounts
+                    .iter()
+                    .map(|a| JPY(balances.get(a).copied().unwrap_or(0)))
+                    .collect(),
+            })
+            .collect();
+
+        Ok(Forecast { accounts, months })
+    }
+
+    /// Expected JPY spending of each budgeted account in `range` after `today`.
+    ///
+    /// The period in progress on `today` only counts what is left of its budget.
+    fn budgeted_spending(&self, today: Date, range: std::ops::Range<Date>) -> BTreeMap<&str, i64> {
+        let mut result: BTreeMap<&str, i64> = BTreeMap::new();
+        let current = self.budgets(today);
+
+        for ((account, code), budget) in self.active_budgets(range.end.add_days(-1)) {
+            if code != CurrencyCode::JPY {
+                continue;
+            }
+
+            let mut period = budget.period.containing(budget.date, range.start);
+            while period.start < range.end {
+                let spending = if period.contains(&today) {
+                    current
+                        .iter()
+                        .find(|q| q.name == account && q.budgeted.code() == code)
+                        .map_or(0, |q| q.remaining.value().max(0))
+                } else if period.start > today && period.start >= range.start {
+                    budget.amount.value()
+                } else {
+                    0
+                };
+                *result.entry(account).or_default() += spending;
+
+                period = budget.period.containing(budget.date, period.end);
+            }
+        }
+
+        result
+    }
+}
+
+fn first_day(date: Date) -> Date {
+    date.add_days(1 - date.day() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Processor;
+    use kakei_types::{currency::JPY, date::Date};
+
+    #[test]
+    fn forecast_monthly_balances() -> anyhow::Result<()> {
+        let src = "\
+2026-03-01 \"Opening\"
+  Assets:Bank  200000 JPY
+  Equity:Opening
+2026-03-10 \"Supermarket\"
+  Expenses:Food  10000 JPY
+  Assets:Bank
+2026-03 budget Expenses:Food 30000 JPY
+~ monthly from 2026-03-25 \"Salary\"
+  Assets:Bank  250000 JPY
+  Income:Salary
+~ monthly from 2026-03-27 \"Rent\"
+  Expenses:Rent  180000 JPY
+  Assets:Bank
+~ yearly from 2026-06-30 \"Insurance\"
+  Expenses:Insurance  150000 JPY
+  Assets:Bank
+";
+        let processor = Processor::from_entries(kakei_parser::parse(src)?)?;
+        let forecast = processor.forecast(Date::new(2026, 3, 15)?, Date::new(2026, 7, 1)?)?;
+
+        assert_eq!(forecast.accounts, vec!["Assets:Bank".to_string()]);
+        let balances: Vec<JPY> = forecast.months.iter().map(|m| m.balances[0]).collect();
+        // 190000 + 250000 - 180000 - 20000 left of March, then +250000 - 180000 - 30000 a month
+        assert_eq!(
+            balances,
+            vec![
+                JPY(240_000),
+                JPY(280_000),
+                JPY(320_000),
+                JPY(210_000),
+                JPY(250_000)
+            ]
+        );
+        assert!(forecast.first_negative().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn yearly_payment_goes_negative() -> anyhow::Result<()> {
+        let src = "\
+2026-03-01 \"Opening\"
+  Assets:Bank  100000 JPY
+  Assets:Wallet  20000 JPY
+  Equity:Opening
+~ monthly from 2026-03-25 \"Salary\"
+  Assets:Bank  250000 JPY
+  Income:Salary
+~ monthly from 2026-03-27 \"Rent\"
+  Expenses:Rent  180000 JPY
+  Assets:Bank
+~ yearly from 2026-05-30 \"Car tax\"
+  Expenses:Tax  400000 JPY
+  Assets:Bank
+";
+        let processor = Processor::from_entries(kakei_parser::parse(src)?)?;
+        let forecast = processor.forecast(Date::new(2026, 3, 15)?, Date::new(2026, 7, 1)?)?;
+
+        let bank: Vec<JPY> = forecast.months.iter().map(|m| m.balances[0]).collect();
+        assert_eq!(
+            bank,
+            vec![
+                JPY(170_000),
+                JPY(240_000),
+                JPY(-90_000),
+                JPY(-20_000),
+                JPY(50_000)
+            ]
+        );
+        // Only the month of the payment, even though June is negative too
+        assert_eq!(
+            forecast.first_negative(),
+            vec![("Assets:Bank".to_string(), Date::new(2026, 5, 1)?)]
+        );
+
+        assert!(!forecast.table(false).to_string().contains('\u{1b}'));
+        assert!(forecast.table(true).to_string().contains('\u{1b}'));
+        Ok(())
+    }
+}
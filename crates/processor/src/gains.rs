@@ -0,0 +1,351 @@
+//! ----- Capital gains -----
+//!
+//! Every `Assets` account holding a currency forms one pool, whose cost basis in JPY is
+//! averaged with either 移動平均法 (moving average) or 総平均法 (total average).
+
+use crate::{Processor, ProcessorError, is_asset_account};
+use kakei_types::{
+    currency::{Amount, CurrencyCode, JPY},
+    date::Date,
+    entry::{Entry, Transaction},
+    query::Disposal,
+};
+use std::collections::BTreeMap;
+use tabled::Table;
+use thiserror::Error;
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum CostMethod {
+    /// 移動平均法, the average cost is updated on every acquisition
+    MovingAverage,
+
+    /// 総平均法, one average cost for every disposal in a year
+    #[default]
+    TotalAverage,
+}
+
+#[derive(Debug, Error)]
+#[error("unknown cost method: \"{0}\", expected moving-average or total-average")]
+pub struct UnknownCostMethod(String);
+
+impl std::fmt::Display for CostMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MovingAverage => write!(f, "moving-average"),
+            Self::TotalAverage => write!(f, "total-average"),
+        }
+    }
+}
+
+impl std::str::FromStr for CostMethod {
+    type Err = UnknownCostMethod;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "moving-average" => Ok(Self::MovingAverage),
+            "total-average" => Ok(Self::TotalAverage),
+            v => Err(UnknownCostMethod(v.to_string())),
+        }
+    }
+}
+
+/// Capital gains of one currency in one year
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapitalGains {
+    pub year: i32,
+    pub method: CostMethod,
+    pub disposals: Vec<Disposal>,
+
+    /// Sum of the gain of every disposal
+    pub realized: JPY,
+
+    /// Units held at the end of the year
+    pub held: Amount,
+
+    /// Cost basis of the units held at the end of the year
+    pub cost_basis: JPY,
+
+    /// Value of the units held at the end of the year, `None` without a price
+    pub market_value: Option<JPY>,
+}
+
+impl CapitalGains {
+    /// `market_value - cost_basis`
+    pub fn unrealized(&self) -> Option<JPY> {
+        self.market_value.map(|value| value - self.cost_basis)
+    }
+
+    pub fn table(&self) -> Table {
+        Table::new(self.disposals.clone())
+    }
+}
+
+/// Change of the pool by one transaction
+#[derive(Debug)]
+struct Movement {
+    date: Date,
+    payee: String,
+    units: i64,
+
+    /// JPY paid for an acquisition or received for a disposal
+    value: i64,
+}
+
+impl Processor {
+    /// Realized gains of `currency` disposed in `year` and unrealized gains at the end of it.
+    pub fn capital_gains(
+        &self,
+        currency: CurrencyCode,
+        method: CostMethod,
+        year: i32,
+    ) -> Result<CapitalGains, ProcessorError> {
+        let year_end = Date::new(year, 12, 31).expect("December 31 is always a valid date");
+
+        let mut movements: Vec<Movement> = Vec::new();
+        for entry in &self.entries {
+            if let Entry::Transaction(transaction) = entry
+                && transaction.date <= year_end
+                && let Some(movement) = self.movement(transaction, currency)?
+            {
+                movements.push(movement);
+            }
+        }
+
+        let (disposals, held, cost_basis) = match method {
+            CostMethod::MovingAverage => moving_average(&movements, currency, year),
+            CostMethod::TotalAverage => total_average(&movements, currency, year),
+        };
+        let market_value = self
+            .prices
+            .convert(Amount::new(currency, held), CurrencyCode::JPY, year_end)?
+            .map(|amount| JPY(amount.value()));
+
+        Ok(CapitalGains {
+            year,
+            method,
+            realized: JPY(disposals.iter().map(|d| d.gain.0).sum()),
+            disposals,
+            held: Amount::new(currency, held),
+            cost_basis: JPY(cost_basis),
+            market_value,
+        })
+    }
+
+    fn movement(
+        &self,
+        transaction: &Transaction,
+        currency: CurrencyCode,
+    ) -> Result<Option<Movement>, ProcessorError> {
+        let postings = transaction.postings.iter().filter(|p| {
+            is_asset_account(&p.account) && p.amount.is_some_and(|a| a.code() == currency)
+        });
+
+        let mut units: i64 = 0;
+        let mut costs: Option<i64> = None;
+        for posting in postings {
+            units += posting.amount.map_or(0, |a| a.value());
+            if posting.cost.is_some()
+                && let Some(weight) = posting.weight()
+            {
+                *costs.get_or_insert(0) += weight.value();
+            }
+        }
+        if units == 0 {
+            // Nothing left the pool, such as a transfer between wallets
+            return Ok(None);
+        }
+
+        let value = match costs {
+            Some(costs) => costs.abs(),
+            None => self
+                .prices
+                .convert(
+                    Amount::new(currency, units.abs()),
+                    CurrencyCode::JPY,
+                    transaction.date,
+                )?
+                .ok_or(ProcessorError::MissingPrice {
+                    base: currency,
+                    quote: CurrencyCode::JPY,
+                    date: transaction.date,
+                })?
+                .value(),
+        };
+
+        Ok(Some(Movement {
+            date: transaction.date,
+            payee: transaction.payee.clone(),
+            units,
+            value,
+        }))
+    }
+}
+
+fn disposal(movement: &Movement, units: Amount, cost_basis: i64) -> Disposal {
+    Disposal {
+        date: movement.date,
+        payee: movement.payee.clone(),
+        units,
+        proceeds: JPY(movement.value),
+        cost_basis: JPY(cost_basis),
+        gain: JPY(movement.value - cost_basis),
+    }
+}
+
+/// `value * numerator / denominator`, rounded half away from zero
+fn prorate(value: i64, numerator: i64, denominator: i64) -> i64 {
+    if denominator == 0 {
+        return 0;
+    }
+
+    let product = value as i128 * numerator as i128;
+    let denominator = denominator as i128;
+    let quotient = product / denominator;
+    let remainder = product % denominator;
+
+    if remainder.abs() * 2 >= denominator.abs() {
+        (quotient + product.signum() * denominator.signum()) as i64
+    } else {
+        quotient as i64
+    }
+}
+
+/// Returns the disposals in `year`, and the units and cost basis held at the end of it.
+fn moving_average(
+    movements: &[Movement],
+    currency: CurrencyCode,
+    year: i32,
+) -> (Vec<Disposal>, i64, i64) {
+    let mut disposals: Vec<Disposal> = Vec::new();
+    let mut units: i64 = 0;
+    let mut cost: i64 = 0;
+
+    for movement in movements {
+        if movement.units > 0 {
+            units += movement.units;
+            cost += movement.value;
+            continue;
+        }
+
+        let sold = -movement.units;
+        let cost_basis = prorate(cost, sold.min(units), units);
+        units -= sold;
+        cost -= cost_basis;
+
+        if movement.date.year() == year {
+            let amount = Amount::new(currency, sold);
+            disposals.push(disposal(movement, amount, cost_basis));
+        }
+    }
+
+    (disposals, units, cost)
+}
+
+/// Returns the disposals in `year`, and the units and cost basis held at the end of it.
+fn total_average(
+    movements: &[Movement],
+    currency: CurrencyCode,
+    year: i32,
+) -> (Vec<Disposal>, i64, i64) {
+    let mut by_year: BTreeMap<i32, Vec<&Movement>> = BTreeMap::new();
+    for movement in movements {
+        by_year
+            .entry(movement.date.year())
+            .or_default()
+            .push(movement);
+    }
+
+    let mut disposals: Vec<Disposal> = Vec::new();
+    let mut units: i64 = 0;
+    let mut cost: i64 = 0;
+
+    for (movement_year, movements_in_year) in by_year {
+        let acquired = movements_in_year.iter().filter(|m| m.units > 0);
+        let acquired_units: i64 = acquired.clone().map(|m| m.units).sum();
+        let acquired_cost: i64 = acquired.map(|m| m.value).sum();
+
+        // The average of the opening balance and every acquisition in the year
+        let average_units = units + acquired_units;
+        let average_cost = cost + acquired_cost;
+
+        let mut sold_units: i64 = 0;
+        for movement in movements_in_year.iter().filter(|m| m.units < 0) {
+            let sold = -movement.units;
+            sold_units += sold;
+
+            if movement_year == year {
+                let cost_basis = prorate(average_cost, sold, average_units);
+                let amount = Amount::new(currency, sold);
+                disposals.push(disposal(movement, amount, cost_basis));
+            }
+        }
+
+        units = average_units - sold_units;
+        cost = prorate(average_cost, units, average_units);
+    }
+
+    (disposals, units, cost)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CostMethod;
+    use crate::Processor;
+    use kakei_types::currency::{Amount, CurrencyCode, JPY, SATS};
+
+    const NOTE: &str = "\
+2025-05-01 \"Buy\"
+  Assets:Wallet  100000 SATS {1000 JPY}
+  Assets:Bank
+2025-06-01 \"Buy\"
+  Assets:Wallet  100000 SATS {2000 JPY}
+  Assets:Bank
+2026-01-10 \"Coffee\"
+  Expenses:Cafe  2000 JPY
+  Assets:Wallet  -50000 SATS {2000 JPY}
+2026-02-01 \"Buy\"
+  Assets:Wallet  100000 SATS {4000 JPY}
+  Assets:Bank
+2026-02-02 \"Cold storage\"
+  Assets:Cold  200000 SATS
+  Assets:Wallet  -200000 SATS
+2026-03-01 \"Headphones\"
+  Expenses:Gadget  5000 JPY
+  Assets:Cold  -100000 SATS {5000 JPY}
+2026-12-31 price SATS 0.05 JPY
+";
+
+    #[test]
+    fn moving_average() -> anyhow::Result<()> {
+        let processor = Processor::from_entries(kakei_parser::parse(NOTE)?)?;
+        let gains = processor.capital_gains(CurrencyCode::SATS, CostMethod::MovingAverage, 2026)?;
+
+        let results: Vec<(i64, i64)> = gains
+            .disposals
+            .iter()
+            .map(|d| (d.cost_basis.0, d.gain.0))
+            .collect();
+        assert_eq!(results, vec![(750, 1250), (2500, 2500)]);
+        assert_eq!(gains.realized, JPY(3750));
+        assert_eq!(gains.held, Amount::SATS(SATS(150_000)));
+        assert_eq!(gains.unrealized(), Some(JPY(3750)));
+        Ok(())
+    }
+
+    #[test]
+    fn total_average() -> anyhow::Result<()> {
+        let processor = Processor::from_entries(kakei_parser::parse(NOTE)?)?;
+        let gains = processor.capital_gains(CurrencyCode::SATS, CostMethod::TotalAverage, 2026)?;
+
+        let results: Vec<(i64, i64)> = gains
+            .disposals
+            .iter()
+            .map(|d| (d.cost_basis.0, d.gain.0))
+            .collect();
+        assert_eq!(results, vec![(1167, 833), (2333, 2667)]);
+        assert_eq!(gains.realized, JPY(3500));
+        assert_eq!(gains.cost_basis, JPY(3500));
+        assert_eq!(gains.unrealized(), Some(JPY(4000)));
+        Ok(())
+    }
+}
@@ -0,0 +1,411 @@
+//! ----- Static HTML site -----
+//!
+//! Every page carries its own styles and SVG charts and links to the others by relative paths,
+//! so the site opens from a folder without a server or a network.
+
+use crate::{Processor, chart::MonthlyTotal};
+use kakei_types::{
+    currency::{Amount, JPY},
+    date::Date,
+};
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// One page of [`Processor::site`]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Page {
+    /// Relative to the root of the site
+    pub path: PathBuf,
+    pub html: String,
+}
+
+const STYLE: &str = "\
+body{font-family:system-ui,sans-serif;max-width:64em;margin:2em auto;padding:0 1em;color:#222}\
+nav a{margin-right:1em}\
+table{border-collapse:collapse;width:100%;margin-bottom:2em}\
+th,td{padding:.25em .5em;border-bottom:1px solid #ddd;text-align:left;vertical-align:top}\
+.amount{text-align:right;font-variant-numeric:tabular-nums;white-space:nowrap}\
+.negative{color:#c00}\
+svg{display:block;margin-bottom:2em}\
+svg text{font-size:12px;fill:#222}";
+
+const INCOME: &str = "#3a8d3a";
+const EXPENSES: &str = "#c44";
+
+impl Processor {
+    /// An index, a register page for every account and a report for every month from the first
+    /// transaction to the last.
+    pub fn site(&self) -> Vec<Page> {
+        let Some(range) = self.span() else {
+            return vec![Page {
+                path: PathBuf::from("index.html"),
+                html: page("kakei", 0, "<p>No transactions yet.</p>"),
+            }];
+        };
+        let first = Date::new(range.start.year(), range.start.month(), 1).unwrap_or(range.start);
+        let months: Vec<MonthlyTotal> = self.monthly_totals(first..range.end);
+
+        let mut pages: Vec<Page> = vec![Page {
+            path: PathBuf::from("index.html"),
+            html: self.index_page(range.clone(), &months),
+        }];
+        for node in self.account_tree(range.clone()) {
+            pages.push(Page {
+                path: PathBuf::from("accounts").join(format!("{}.html", slug(&node.name))),
+                html: self.account_page(&node.name, range.clone()),
+            });
+        }
+        for (index, total) in months.iter().enumerate() {
+            pages.push(Page {
+                path: PathBuf::from("months").join(format!("{}.html", month_label(total.month))),
+                html: self.month_page(
+                    total,
+                    index.checked_sub(1).and_then(|i| months.get(i)),
+                    months.get(index + 1),
+                ),
+            });
+        }
+
+        pages
+    }
+
+    /// From the first transaction to the day after the last
+    fn span(&self) -> Option<Range<Date>> {
+        let mut dates = self.transactions().map(|t| t.date);
+        let first: Date = dates.next()?;
+        Some(first..dates.last().unwrap_or(first).add_days(1))
+    }
+
+    fn index_page(&self, range: Range<Date>, months: &[MonthlyTotal]) -> String {
+        let mut body = format!(
+            "<p>{} to {}</p><h2>Accounts</h2><table><tr><th>Account</th>\
+             <th class=\"amount\">Total</th></tr>",
+            range.start,
+            range.end.add_days(-1)
+        );
+        for node in self.account_tree(range) {
+            let name = node.name.rsplit(':').next().unwrap_or(&node.name);
+            body.push_str(&format!(
+                "<tr><td style=\"padding-left:{}em\"><a href=\"accounts/{}.html\">{}</a></td>{}</tr>",
+                0.5 + node.depth as f64 * 1.5,
+                slug(&node.name),
+                escape(name),
+                amounts(&node.totals)
+            ));
+        }
+        body.push_str("</table><h2>Income and expenses</h2>");
+        body.push_str(&columns(months));
+
+        body.push_str(
+            "<h2>Months</h2><table><tr><th>Month</th><th class=\"amount\">Income</th>\
+             <th class=\"amount\">Expenses</th><th class=\"amount\">Savings</th></tr>",
+        );
+        for total in months.iter().rev() {
+            let label = month_label(total.month);
+            body.push_str(&format!(
+                "<tr><td><a href=\"months/{label}.html\">{label}</a></td>{}{}{}</tr>",
+                jpy(total.income),
+                jpy(total.expenses),
+                jpy(total.income - total.expenses)
+            ));
+        }
+        body.push_str("</table>");
+
+        page("kakei", 0, &body)
+    }
+
+    fn account_page(&self, account: &str, range: Range<Date>) -> String {
+        let rows = self.register(account, range);
+        let balances: Vec<(Date, i64)> = rows
+            .iter()
+            .filter_map(|row| match row.balance {
+                Amount::JPY(JPY(value)) => Some((row.date, value)),
+                _ => None,
+            })
+            .collect();
+
+        let mut body = String::new();
+        if balances.len() > 1 {
+            body.push_str(&line(&balances));
+        }
+        body.push_str(
+            "<table><tr><th>Date</th><th>Payee</th><th>Account</th>\
+             <th class=\"amount\">Amount</th><th class=\"amount\">Balance</th></tr>",
+        );
+        for row in &rows {
+            // The other side of the transaction, unless it was posted to a sub-account
+            let other: String = if row.account == account {
+                row.counterparts.join(", ")
+            } else {
+                row.account.clone()
+            };
+            body.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td>{}{}</tr>",
+                row.date,
+                escape(&row.payee),
+                escape(&other),
+                amounts(&[row.amount]),
+                amounts(&[row.balance])
+            ));
+        }
+        body.push_str("</table>");
+
+        page(account, 1, &body)
+    }
+
+    fn month_page(
+        &self,
+        total: &MonthlyTotal,
+        previous: Option<&MonthlyTotal>,
+        next: Option<&MonthlyTotal>,
+    ) -> String {
+        let range = total.month..total.month.add_months(1);
+        let mut body = String::from("<nav>");
+        for (text, other) in [("Previous month", previous), ("Next month", next)] {
+            if let Some(other) = other {
+                let label = month_label(other.month);
+                body.push_str(&format!("<a href=\"{label}.html\">{text}: {label}</a>"));
+            }
+        }
+        body.push_str(&format!(
+            "</nav><table><tr><th>Income</th>{}</tr><tr><th>Expenses</th>{}</tr>\
+             <tr><th>Savings</th>{}</tr></table>",
+            jpy(total.income),
+            jpy(total.expenses),
+            jpy(total.income - total.expenses)
+        ));
+
+        let spending = self.spending_by_category(range.clone());
+        if !spending.is_empty() {
+            body.push_str("<h2>Spending by category</h2>");
+            body.push_str(&bars(&spending));
+        }
+
+        body.push_str("<h2>Transactions</h2><table><tr><th>Date</th><th>Payee</th><th>Account</th><th class=\"amount\">Amount</th></tr>");
+        for transaction in self.transactions().filter(|t| range.contains(&t.date)) {
+            for (index, posting) in transaction.postings.iter().enumerate() {
+                let (date, payee) = match index {
+                    0 => (transaction.date.to_string(), escape(&transaction.payee)),
+                    _ => (String::new(), String::new()),
+                };
+                body.push_str(&format!(
+                    "<tr><td>{date}</td><td>{payee}</td><td><a href=\"../accounts/{}.html\">{}</a></td>{}</tr>",
+                    slug(&posting.account),
+                    escape(&posting.account),
+                    amounts(&posting.amount.into_iter().collect::<Vec<_>>())
+                ));
+            }
+        }
+        body.push_str("</table>");
+
+        page(&month_label(total.month), 1, &body)
+    }
+}
+
+/// A whole page `depth` directories below the root of the site
+fn page(title: &str, depth: usize, body: &str) -> String {
+    let root = "../".repeat(depth);
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title} - kakei</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <nav><a href=\"{root}index.html\">Index</a></nav>\n<h1>{title}</h1>\n{body}\n</body>\n</html>\n",
+        title = escape(title)
+    )
+}
+
+/// File name of the register page of `account`, which is safe since accounts have no dots
+fn slug(account: &str) -> String {
+    account.replace(':', ".")
+}
+
+fn month_label(month: Date) -> String {
+    format!("{:04}-{:02}", month.year(), month.month())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn amounts(amounts: &[Amount]) -> String {
+    let negative = amounts.iter().any(|a| a.value() < 0);
+    let text: Vec<String> = amounts.iter().map(Amount::to_string).collect();
+    format!(
+        "<td class=\"amount{}\">{}</td>",
+        if negative { " negative" } else { "" },
+        text.join("<br>")
+    )
+}
+
+fn jpy(value: i64) -> String {
+    amounts(&[Amount::JPY(JPY(value))])
+}
+
+/// Income and expenses side by side for each month
+fn columns(months: &[MonthlyTotal]) -> String {
+    const HEIGHT: i64 = 160;
+    const COLUMN: usize = 14;
+    const STEP: usize = COLUMN * 2 + 8;
+
+    let max: i64 = months
+        .iter()
+        .map(|m| m.income.max(m.expenses))
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let height = |value: i64| value.max(0) * HEIGHT / max;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
+        months.len() * STEP,
+        HEIGHT + 36
+    );
+    for (index, total) in months.iter().enumerate() {
+        let x = index * STEP;
+        let label = month_label(total.month);
+        for (offset, value, color, name) in [
+            (0, total.income, INCOME, "income"),
+            (COLUMN, total.expenses, EXPENSES, "expenses"),
+        ] {
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{COLUMN}\" height=\"{}\" fill=\"{color}\">\
+                 <title>{label} {name}: {}</title></rect>",
+                x + offset,
+                HEIGHT - height(value),
+                height(value),
+                JPY(value)
+            ));
+        }
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{:02}</text>",
+            x + COLUMN,
+            HEIGHT + 14,
+            total.month.month()
+        ));
+        if index == 0 || total.month.month() == 1 {
+            svg.push_str(&format!(
+                "<text x=\"{x}\" y=\"{}\">{}</text>",
+                HEIGHT + 30,
+                total.month.year()
+            ));
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// One labelled horizontal bar for each of `bars`
+fn bars(bars: &[(String, i64)]) -> String {
+    const LABEL: i64 = 200;
+    const WIDTH: i64 = 360;
+    const ROW: usize = 24;
+
+    let max: i64 = bars.iter().map(|(_, v)| *v).max().unwrap_or(0).max(1);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
+        LABEL + WIDTH + 120,
+        bars.len() * ROW
+    );
+    for (index, (label, value)) in bars.iter().enumerate() {
+        let y = index * ROW;
+        let width = value.max(&0) * WIDTH / max;
+        svg.push_str(&format!(
+            "<text x=\"0\" y=\"{}\">{}</text>\
+             <rect x=\"{LABEL}\" y=\"{}\" width=\"{width}\" height=\"{}\" fill=\"{EXPENSES}\"/>\
+             <text x=\"{}\" y=\"{}\">{}</text>",
+            y + 16,
+            escape(label),
+            y + 4,
+            ROW - 8,
+            LABEL + width + 6,
+            y + 16,
+            JPY(*value)
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// A step line of a balance over time, with the zero line when it goes negative
+fn line(points: &[(Date, i64)]) -> String {
+    const WIDTH: i64 = 640;
+    const HEIGHT: i64 = 160;
+
+    let first: i64 = points.first().map_or(0, |(d, _)| d.to_days());
+    let days: i64 = (points.last().map_or(0, |(d, _)| d.to_days()) - first).max(1);
+    let low: i64 = points.iter().map(|(_, v)| *v).min().unwrap_or(0).min(0);
+    let high: i64 = points
+        .iter()
+        .map(|(_, v)| *v)
+        .max()
+        .unwrap_or(0)
+        .max(low + 1);
+    let x = |date: &Date| (date.to_days() - first) * WIDTH / days;
+    let y = |value: i64| HEIGHT - (value - low) * HEIGHT / (high - low);
+
+    let mut path = format!("M0 {}", y(0));
+    for (date, value) in points {
+        path.push_str(&format!(" H{} V{}", x(date), y(*value)));
+    }
+    path.push_str(&format!(" H{WIDTH}"));
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{}\">\
+         <line x1=\"0\" y1=\"{zero}\" x2=\"{WIDTH}\" y2=\"{zero}\" stroke=\"#999\"/>\
+         <path d=\"{path}\" fill=\"none\" stroke=\"{INCOME}\" stroke-width=\"2\"/></svg>",
+        HEIGHT + 1,
+        zero = y(0)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Processor;
+    use std::path::PathBuf;
+
+    #[test]
+    fn site() -> anyhow::Result<()> {
+        let src = r#"
+2026-02-25 "Salary"
+  Assets:Bank       300000 JPY
+  Income:Salary
+
+2026-03-01 "Tom & Jerry's <Diner>"
+  Expenses:Food     1200 JPY
+  Assets:Bank
+"#;
+        let processor = Processor::from_entries(kakei_parser::parse(src)?)?;
+        let pages = processor.site();
+        let paths: Vec<&PathBuf> = pages.iter().map(|p| &p.path).collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                &PathBuf::from("index.html"),
+                &PathBuf::from("accounts/Assets.html"),
+                &PathBuf::from("accounts/Assets.Bank.html"),
+                &PathBuf::from("accounts/Expenses.html"),
+                &PathBuf::from("accounts/Expenses.Food.html"),
+                &PathBuf::from("accounts/Income.html"),
+                &PathBuf::from("accounts/Income.Salary.html"),
+                &PathBuf::from("months/2026-02.html"),
+                &PathBuf::from("months/2026-03.html"),
+            ]
+        );
+        assert!(
+            pages[0]
+                .html
+                .contains("<a href=\"months/2026-03.html\">2026-03</a>")
+        );
+        assert!(pages[2].html.contains("<svg"));
+        assert!(pages[8].html.contains("Tom &amp; Jerry's &lt;Diner&gt;"));
+        assert!(
+            pages[8]
+                .html
+                .contains("<a href=\"2026-02.html\">Previous month: 2026-02</a>")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,528 @@
+//! ----- Importing bank statements -----
+//!
+//! A [`CsvProfile`] describes how one bank or card company lays out its CSV statements, and
+//! [`Rules`] choose the account of each line from its payee.
+
+use crate::encoding::Encoding;
+use kakei_types::{
+    currency::{Amount, CurrencyCode},
+    date::Date,
+    entry::{Posting, Transaction},
+};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Account of lines paying out which no rule matches
+pub const UNCATEGORIZED_EXPENSES: &str = "Expenses:Uncategorized";
+
+/// Account of lines paying in which no rule matches
+pub const UNCATEGORIZED_INCOME: &str = "Income:Uncategorized";
+
+/// Metadata key of the reference number the bank gives a statement line
+pub const REFERENCE: &str = "ref";
+
+/// Layout of the CSV statements of one account.
+///
+/// ```toml
+/// [import.profiles.mizuho]
+/// account = "Assets:Bank:Mizuho"
+/// encoding = "shift_jis"
+/// date = "取引日"
+/// date_format = "%Y/%m/%d"
+/// payee = "摘要"
+/// withdrawal = "お引出し"
+/// deposit = "お預入れ"
+/// reference = "お取引番号"
+///
+/// [import.profiles.card]
+/// account = "Liabilities:Card"
+/// headers = false
+/// date = 0
+/// payee = 1
+/// amount = 2
+/// sign = "inverted"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CsvProfile {
+    /// Account the statement belongs to
+    pub account: String,
+
+    /// Encoding of the statements, detected from each file when left out
+    pub encoding: Option<Encoding>,
+
+    pub date: Column,
+
+    /// `%Y`, `%y`, `%m` and `%d` stand for the numbers, anything else is written as it is
+    #[serde(default = "default_date_format")]
+    pub date_format: String,
+
+    pub payee: Column,
+
+    /// One column of signed amounts. Leave it out for separate withdrawal and deposit columns
+    pub amount: Option<Column>,
+    pub withdrawal: Option<Column>,
+    pub deposit: Option<Column>,
+
+    #[serde(default)]
+    pub sign: Sign,
+
+    /// Reference number of each line, kept as `ref` metadata to recognize the line when the
+    /// statement is imported again
+    pub reference: Option<Column>,
+
+    /// Whether the first line after `skip` names the columns
+    #[serde(default = "default_headers")]
+    pub headers: bool,
+
+    /// Lines to skip at the start, such as an account summary above the header
+    #[serde(default)]
+    pub skip: usize,
+}
+
+fn default_date_format() -> String {
+    "%Y-%m-%d".to_string()
+}
+
+fn default_headers() -> bool {
+    true
+}
+
+/// A column named in the header, or counted from 0
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Column {
+    Index(usize),
+    Name(String),
+}
+
+impl std::fmt::Display for Column {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Index(index) => write!(f, "{index}"),
+            Self::Name(name) => write!(f, "\"{name}\""),
+        }
+    }
+}
+
+/// What a positive amount means
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Sign {
+    /// Money paid into the account, as bank statements show it
+    #[default]
+    Normal,
+
+    /// Money paid out of the account, as card statements list charges
+    Inverted,
+}
+
+/// Accounts chosen by the payee of a statement line. The first matching rule wins.
+///
+/// ```toml
+/// [[rule]]
+/// payee = "(?i)seven.?eleven|セブン"
+/// account = "Expenses:Food"
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Rules {
+    rules: Vec<(Regex, String)>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RulesFile {
+    #[serde(default)]
+    rule: Vec<RuleEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuleEntry {
+    payee: String,
+    account: String,
+}
+
+impl Rules {
+    pub fn parse(text: &str) -> Result<Self, ImportError> {
+        let file: RulesFile =
+            toml::from_str(text).map_err(|e| ImportError::Rules(e.message().to_string()))?;
+        let rules = file
+            .rule
+            .into_iter()
+            .map(|rule| {
+                let regex =
+                    Regex::new(&rule.payee).map_err(|e| ImportError::Rules(e.to_string()))?;
+                Ok((regex, rule.account))
+            })
+            .collect::<Result<_, ImportError>>()?;
+
+        Ok(Self { rules })
+    }
+
+    pub fn account(&self, payee: &str) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|(regex, _)| regex.is_match(payee))
+            .map(|(_, account)| account.as_str())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+
+    #[error("no column {0} in the statement")]
+    Column(Column),
+
+    #[error("the profile needs either amount, or withdrawal and deposit")]
+    NoAmount,
+
+    #[error("line {line}: {message}")]
+    Invalid { line: u64, message: String },
+
+    #[error("invalid rules: {0}")]
+    Rules(String),
+
+    #[error("invalid OFX: {0}")]
+    Ofx(String),
+
+    #[error("no kakei account for the OFX account {0}, add it under [import.accounts]")]
+    UnknownAccount(String),
+
+    #[error("the statement is in {0}, kakei records JPY")]
+    Currency(String),
+
+    #[error("the OFX transaction {transaction} is in {currency}, kakei imports JPY only")]
+    OfxCurrency {
+        transaction: String,
+        currency: String,
+    },
+
+    #[error("invalid mapping: {0}")]
+    Mapping(String),
+
+    #[error("no kakei account for {0}, add them under [accounts] in the mapping")]
+    UnmappedAccounts(String),
+}
+
+/// Transactions of the statement of one account
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    /// The kakei account of the statement
+    pub account: String,
+    pub transactions: Vec<Transaction>,
+}
+
+/// Reads a CSV statement into one transaction per line, between the account of the profile
+/// and the account the rules choose.
+pub fn read_statement(
+    text: &str,
+    profile: &CsvProfile,
+    rules: &Rules,
+) -> Result<Vec<Transaction>, ImportError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let mut records = reader.records().skip(profile.skip);
+
+    let header: Vec<String> = match profile.headers {
+        true => match records.next() {
+            Some(record) => record?.iter().map(ToString::to_string).collect(),
+            None => return Ok(Vec::new()),
+        },
+        false => Vec::new(),
+    };
+    let index = |column: &Column| -> Result<usize, ImportError> {
+        match column {
+            Column::Index(index) => Ok(*index),
+            Column::Name(name) => header
+                .iter()
+                .position(|h| h == name)
+                .ok_or_else(|| ImportError::Column(column.clone())),
+        }
+    };
+    let date_column = index(&profile.date)?;
+    let payee_column = index(&profile.payee)?;
+    let amount_columns = match (&profile.amount, &profile.withdrawal, &profile.deposit) {
+        (Some(amount), _, _) => (Some(index(amount)?), None, None),
+        (None, None, None) => return Err(ImportError::NoAmount),
+        (None, withdrawal, deposit) => (
+            None,
+            withdrawal.as_ref().map(index).transpose()?,
+            deposit.as_ref().map(index).transpose()?,
+        ),
+    };
+
+    let reference_column = profile.reference.as_ref().map(index).transpose()?;
+
+    let mut transactions: Vec<Transaction> = Vec::new();
+    for record in records {
+        let record = record?;
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        let line = record.position().map_or(0, |p| p.line());
+        let invalid = |message: String| ImportError::Invalid { line, message };
+        let field = |column: usize| -> Result<&str, ImportError> {
+            record
+                .get(column)
+                .ok_or_else(|| invalid(format!("no column {column}")))
+        };
+        let number = |column: Option<usize>| -> Result<i64, ImportError> {
+            match column {
+                Some(column) => {
+                    let text = field(column)?;
+                    parse_number(text).ok_or_else(|| invalid(format!("invalid amount: \"{text}\"")))
+                }
+                None => Ok(0),
+            }
+        };
+
+        let text = field(date_column)?;
+        let date = parse_date(text, &profile.date_format).ok_or_else(|| {
+            invalid(format!(
+                "invalid date: \"{text}\", expected {}",
+                profile.date_format
+            ))
+        })?;
+        let payee = field(payee_column)?;
+        let mut change: i64 = match amount_columns {
+            (Some(amount), _, _) => number(Some(amount))?,
+            (None, withdrawal, deposit) => number(deposit)? - number(withdrawal)?,
+        };
+        if profile.sign == Sign::Inverted {
+            change = -change;
+        }
+
+        let metadata: Vec<(String, String)> = match reference_column {
+            Some(column) if !field(column)?.is_empty() => {
+                vec![(REFERENCE.to_string(), field(column)?.to_string())]
+            }
+            _ => Vec::new(),
+        };
+
+        transactions.push(statement_line(
+            date,
+            payee,
+            change,
+            &profile.account,
+            rules,
+            metadata,
+        ));
+    }
+
+    Ok(transactions)
+}
+
+/// The transaction of one statement line changing `account` by `change`, between the account
+/// the rules choose and `account`, whose amount is left out.
+pub(crate) fn statement_line(
+    date: Date,
+    payee: &str,
+    change: i64,
+    account: &str,
+    rules: &Rules,
+    metadata: Vec<(String, String)>,
+) -> Transaction {
+    let category: &str = rules
+        .account(payee)
+        .unwrap_or_else(|| uncategorized(change));
+
+    record(date, payee, change, account, category, metadata)
+}
+
+/// The account of a line changing its account by `change` which no rule or mapping places
+pub(crate) fn uncategorized(change: i64) -> &'static str {
+    if change > 0 {
+        UNCATEGORIZED_INCOME
+    } else {
+        UNCATEGORIZED_EXPENSES
+    }
+}
+
+/// The transaction changing `account` by `change`, between `category` and `account`, whose
+/// amount is left out. Line breaks and other control characters of a quoted field become
+/// spaces, as the payee and the metadata are written on one line of the note.
+pub(crate) fn record(
+    date: Date,
+    payee: &str,
+    change: i64,
+    account: &str,
+    category: &str,
+    metadata: Vec<(String, String)>,
+) -> Transaction {
+    Transaction {
+        date,
+        // A quote would end the payee written in the note
+        payee: one_line(payee).replace('"', "'"),
+        postings: vec![
+            Posting {
+                account: category.to_string(),
+                amount: Some(Amount::new(CurrencyCode::JPY, -change)),
+                cost: None,
+            },
+            Posting {
+                account: account.to_string(),
+                amount: None,
+                cost: None,
+            },
+        ],
+        metadata: metadata
+            .into_iter()
+            .map(|(key, value)| (key, one_line(&value)))
+            .collect(),
+        generated: false,
+    }
+}
+
+fn one_line(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect()
+}
+
+/// Reads `text` as `format`, such as `2026/3/14` as `%Y/%m/%d`.
+pub fn parse_date(text: &str, format: &str) -> Option<Date> {
+    let mut text = text.chars().peekable();
+    let (mut year, mut month, mut day) = (None, None, None);
+    let mut format = format.chars();
+
+    while let Some(c) = format.next() {
+        if c != '%' {
+            if text.next() != Some(c) {
+                return None;
+            }
+            continue;
+        }
+
+        let (field, digits) = match format.next()? {
+            'Y' => (&mut year, 4),
+            'y' => (&mut year, 2),
+            'm' => (&mut month, 2),
+            'd' => (&mut day, 2),
+            _ => return None,
+        };
+        let mut number = String::new();
+        while number.len() < digits
+            && let Some(digit) = text.next_if(char::is_ascii_digit)
+        {
+            number.push(digit);
+        }
+        *field = Some((number.parse::<u32>().ok()?, number.len()));
+    }
+    if text.next().is_some() {
+        return None;
+    }
+
+    let (year, digits) = year?;
+    let year = if digits == 2 { 2000 + year } else { year };
+    Date::new(year as i32, month?.0, day?.0).ok()
+}
+
+/// Reads `1,200`, `-980`, `¥1,200` or `(1,200)` for a negative number. Empty is 0.
+pub fn parse_number(text: &str) -> Option<i64> {
+    let text: String = text
+        .chars()
+        .filter(|c| !matches!(c, ',' | '¥' | '\\' | ' '))
+        .collect();
+    if text.is_empty() {
+        return Some(0);
+    }
+
+    match text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        Some(negative) => negative.parse::<i64>().ok().map(|n| -n),
+        None => text.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CsvProfile, REFERENCE, Rules, UNCATEGORIZED_EXPENSES, parse_date, read_statement};
+    use kakei_types::{
+        currency::{Amount, JPY},
+        date::Date,
+    };
+
+    #[test]
+    fn statements() -> anyhow::Result<()> {
+        assert_eq!(
+            parse_date("2026/3/14", "%Y/%m/%d"),
+            Some(Date::new(2026, 3, 14)?)
+        );
+        assert_eq!(
+            parse_date("14.03.26", "%d.%m.%y"),
+            Some(Date::new(2026, 3, 14)?)
+        );
+        assert_eq!(parse_date("2026-03-14", "%Y/%m/%d"), None);
+
+        let rules = Rules::parse(
+            r#"
+[[rule]]
+payee = "(?i)matsuya"
+account = "Expenses:Food"
+
+[[rule]]
+payee = "給与"
+account = "Income:Salary"
+"#,
+        )?;
+        let bank: CsvProfile = toml::from_str(
+            r#"
+account = "Assets:Bank"
+date = "取引日"
+date_format = "%Y/%m/%d"
+payee = "摘要"
+withdrawal = "お引出し"
+deposit = "お預入れ"
+skip = 1
+"#,
+        )?;
+        let statement = "\
+口座番号,1234567
+取引日,摘要,お引出し,お預入れ,残高
+2026/03/01,MATSUYA SHIBUYA,\"1,200\",,98800
+2026/03/25,給与,,\"300,000\",398800
+
+2026/03/27,ATM,\"10,000\",,388800
+";
+        let transactions = read_statement(statement, &bank, &rules)?;
+        assert_eq!(transactions.len(), 3);
+        assert_eq!(
+            transactions[0].to_string(),
+            "2026-03-01 \"MATSUYA SHIBUYA\"\n  Expenses:Food  1200 JPY\n  Assets:Bank\n"
+        );
+        assert_eq!(
+            transactions[1].postings[0].amount,
+            Some(Amount::JPY(JPY(-300000)))
+        );
+        assert_eq!(transactions[2].postings[0].account, UNCATEGORIZED_EXPENSES);
+
+        let card: CsvProfile = toml::from_str(
+            r#"
+account = "Liabilities:Card"
+headers = false
+date = 0
+payee = 1
+amount = 2
+sign = "inverted"
+reference = 3
+"#,
+        )?;
+        let transactions = read_statement("2026-03-02,Matsuya,980,A-0001\n", &card, &rules)?;
+        assert_eq!(
+            transactions[0].postings[0].amount,
+            Some(Amount::JPY(JPY(980)))
+        );
+        assert_eq!(transactions[0].metadata(REFERENCE), Some("A-0001"));
+        assert!(read_statement("2026-03-02,Matsuya,abc\n", &card, &rules).is_err());
+
+        let multiline = "2026-03-03,\"Matsuya\r\nShibuya\",500,\"A-\n0002\"\n";
+        let transactions = read_statement(multiline, &card, &rules)?;
+        assert_eq!(transactions[0].payee, "Matsuya  Shibuya");
+        assert_eq!(transactions[0].metadata(REFERENCE), Some("A- 0002"));
+        assert_eq!(transactions[0].to_string().lines().count(), 4);
+        Ok(())
+    }
+}
@@ -0,0 +1,636 @@
+//! ----- Ledger journals -----
+//!
+//! ledger and hledger share a journal format much wider than a kakeibo note. The common subset is
+//! read: transactions with their postings, comments and tags, `P` price lines and `account`
+//! directives. Periodic and automated transactions, and directives which only change how amounts
+//! are shown, are skipped. Amounts must be in yen, sats or bitcoin, which kakei holds as sats.
+//!
+//! Written the other way, a note becomes a journal both ledger and hledger read, with budgets and
+//! recurring transactions as periodic transactions for `hledger balance --budget` and `--forecast`.
+
+use crate::{
+    Processor,
+    import::{ImportError, parse_date},
+};
+use kakei_types::{
+    currency::{Amount, CurrencyCode},
+    date::Date,
+    entry::{Entry, Open, Posting, Price, Transaction},
+    rate::Rate,
+};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// Metadata key of the `:tags:` of a journal, which have no value, separated by `, `
+pub const TAGS: &str = "tags";
+
+/// Metadata key of the `(code)` of a transaction, such as a check number
+pub const CODE: &str = "code";
+
+/// Directives which only change how amounts and accounts are shown
+const IGNORED: [&str; 10] = [
+    "commodity",
+    "D",
+    "decimal-mark",
+    "payee",
+    "tag",
+    "alias",
+    "apply",
+    "end",
+    "bucket",
+    "A",
+];
+
+/// Entries read from a journal
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Journal {
+    /// Accounts of `account` directives, opened on the first date of the journal
+    pub opens: Vec<Open>,
+    pub prices: Vec<Price>,
+    pub transactions: Vec<Transaction>,
+}
+
+/// Reads the transactions, prices and account directives of a ledger or hledger journal.
+pub fn read_journal(text: &str) -> Result<Journal, ImportError> {
+    let mut journal = Journal::default();
+    let mut accounts: Vec<String> = Vec::new();
+    let mut year: Option<i32> = None;
+    let mut in_transaction: bool = false;
+    let mut in_comment: bool = false;
+
+    for (line, number) in text.lines().zip(1u64..) {
+        let invalid = |message: String| ImportError::Invalid {
+            line: number,
+            message,
+        };
+        let trimmed: &str = line.trim();
+        if in_comment {
+            in_comment = !matches!(trimmed, "end comment" | "end test");
+            continue;
+        }
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if line.starts_with([' ', '\t']) {
+            // Sub-directives of skipped blocks are skipped with them
+            if in_transaction && let Some(transaction) = journal.transactions.last_mut() {
+                posting_line(transaction, trimmed).map_err(invalid)?;
+            }
+            continue;
+        }
+        in_transaction = false;
+
+        let (word, rest) = trimmed
+            .split_once(char::is_whitespace)
+            .map_or((trimmed, ""), |(word, rest)| (word, rest.trim()));
+        match word {
+            _ if trimmed.starts_with([';', '#', '%', '|', '*']) => {}
+            "comment" | "test" => in_comment = true,
+            _ if trimmed.starts_with(['~', '=']) => {}
+            _ if trimmed.starts_with(|c: char| c.is_ascii_digit()) => {
+                journal
+                    .transactions
+                    .push(header(trimmed, year).map_err(invalid)?);
+                in_transaction = true;
+            }
+            "P" => journal.prices.push(price(rest, year).map_err(invalid)?),
+            "account" => {
+                let (account, _) = split_comment(rest);
+                accounts.push(account_name(account.trim()));
+            }
+            "Y" | "year" => {
+                year = Some(
+                    rest.parse()
+                        .map_err(|_| invalid(format!("invalid year: {rest}")))?,
+                );
+            }
+            "include" => {
+                return Err(invalid(format!("{rest} is included, import it on its own")));
+            }
+            _ if IGNORED.contains(&word) => {}
+            _ => return Err(invalid(format!("unsupported directive: {word}"))),
+        }
+    }
+
+    let first: Option<Date> = journal
+        .transactions
+        .iter()
+        .map(|t| t.date)
+        .chain(journal.prices.iter().map(|p| p.date))
+        .min();
+    if let Some(date) = first {
+        let mut opened: BTreeSet<String> = BTreeSet::new();
+        journal.opens = accounts
+            .into_iter()
+            .filter(|account| opened.insert(account.clone()))
+            .map(|account| Open {
+                date,
+                account,
+                currencies: Vec::new(),
+            })
+            .collect();
+    }
+
+    Ok(journal)
+}
+
+/// `2026/03/14=2026/03/16 * (1024) Matsuya  ; :food:`
+fn header(line: &str, year: Option<i32>) -> Result<Transaction, String> {
+    let (line, comment) = split_comment(line);
+    let (date, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    // The secondary date after `=` is when the bank cleared it
+    let date: Date = date_of(date.split('=').next().unwrap_or_default(), year)?;
+
+    let mut rest: &str = rest.trim_start();
+    rest = rest.strip_prefix(['*', '!']).unwrap_or(rest).trim_start();
+    let mut metadata: Vec<(String, String)> = Vec::new();
+    if let Some(code) = rest.strip_prefix('(')
+        && let Some((code, after)) = code.split_once(')')
+    {
+        metadata.push((CODE.to_string(), code.to_string()));
+        rest = after.trim_start();
+    }
+    tags(comment, &mut metadata);
+
+    Ok(Transaction {
+        date,
+        payee: rest.trim_end().replace('"', "'"),
+        postings: Vec::new(),
+        metadata,
+        generated: false,
+    })
+}
+
+/// `Expenses:Food  980 JPY  ; note`, or a comment line, of `transaction`
+fn posting_line(transaction: &mut Transaction, line: &str) -> Result<(), String> {
+    let (body, comment) = split_comment(line);
+    tags(comment, &mut transaction.metadata);
+    if body.trim().is_empty() {
+        return Ok(());
+    }
+
+    let body: &str = body.strip_prefix(['*', '!']).map_or(body, str::trim_start);
+    let end: Option<usize> = [body.find("  "), body.find('\t')]
+        .into_iter()
+        .flatten()
+        .min();
+    let (account, amount) = match end {
+        Some(end) => (&body[..end], body[end..].trim()),
+        None => (body.trim_end(), ""),
+    };
+    // `(Assets:Cash)` is left out of the balance, which kakei does not allow
+    if account.starts_with('(') {
+        return Ok(());
+    }
+
+    let (amount, cost) = posting_amount(amount)?;
+    transaction.postings.push(Posting {
+        account: account_name(account.trim_start_matches('[').trim_end_matches(']')),
+        amount,
+        cost,
+    });
+    Ok(())
+}
+
+/// `0.001 BTC {9000000 JPY} @ 9500000 JPY = 0.002 BTC` as the amount and the whole value paid or
+/// received, preferring the price to the lot cost. The balance assertion after `=` is dropped.
+fn posting_amount(text: &str) -> Result<(Option<Amount>, Option<Amount>), String> {
+    let text: &str = text.split('=').next().unwrap_or_default().trim();
+    if text.is_empty() {
+        return Ok((None, None));
+    }
+
+    let (text, lot): (String, Option<(&str, bool)>) = match text.split_once('{') {
+        Some((before, after)) => {
+            let total: bool = after.starts_with('{');
+            let (lot, after) = after
+                .trim_start_matches('{')
+                .split_once('}')
+                .ok_or_else(|| format!("unclosed lot cost: {text}"))?;
+            (
+                format!("{before} {}", after.trim_start_matches('}')),
+                Some((lot, total)),
+            )
+        }
+        None => (text.to_string(), None),
+    };
+    let (units, price): (&str, Option<(&str, bool)>) = match text.split_once('@') {
+        Some((units, price)) => match price.strip_prefix('@') {
+            Some(total) => (units, Some((total, true))),
+            None => (units, Some((price, false))),
+        },
+        None => (&text, None),
+    };
+
+    let units: Quantity = Quantity::parse(units)?;
+    let cost: Option<Amount> = match price.or(lot) {
+        Some((price, total)) => {
+            let price: Quantity = Quantity::parse(price)?;
+            let value: i64 = if total {
+                price.value()?.abs()
+            } else {
+                price.times(&units)
+            };
+            Some(Amount::new(price.code, value))
+        }
+        None => None,
+    };
+
+    Ok((Some(Amount::new(units.code, units.value()?)), cost))
+}
+
+/// `P 2026/03/14 BTC 9500000 JPY`, with an optional time after the date
+fn price(text: &str, year: Option<i32>) -> Result<Price, String> {
+    let mut words = text.split_whitespace();
+    let date: Date = date_of(words.next().unwrap_or_default(), year)?;
+    let mut base: &str = words.next().unwrap_or_default();
+    if base.contains(':') {
+        base = words.next().unwrap_or_default();
+    }
+    let (base, exponent): (CurrencyCode, u32) = commodity(base.trim_matches('"'))?;
+    let price: Quantity = Quantity::parse(&words.collect::<Vec<_>>().join(" "))?;
+
+    // One kakei unit of `base` in kakei units of the quote, such as 0.095 JPY per sat
+    let (mut mantissa, scale) = (price.mantissa, price.scale as i64 - price.exponent as i64);
+    let scale: i64 = scale + exponent as i64;
+    let scale: u32 = if scale < 0 {
+        mantissa *= 10i128.pow(scale.unsigned_abs() as u32);
+        0
+    } else {
+        scale as u32
+    };
+    let rate: Rate = decimal(mantissa, scale)
+        .parse()
+        .map_err(|e| format!("{e}"))?;
+
+    Ok(Price {
+        date,
+        base,
+        rate,
+        quote: price.code,
+    })
+}
+
+/// `2026/03/14`, `2026-03-14` or `2026.03.14`, or `03/14` in the year of a `year` directive
+fn date_of(text: &str, year: Option<i32>) -> Result<Date, String> {
+    let text: String = text.replace(['-', '.'], "/");
+    let text: String = match (text.matches('/').count(), year) {
+        (1, Some(year)) => format!("{year}/{text}"),
+        _ => text,
+    };
+    parse_date(&text, "%Y/%m/%d").ok_or_else(|| format!("invalid date: {text}"))
+}
+
+/// `Assets:Checking Account` as `Assets:Checking-Account`, as a segment of a kakei account holds
+/// only letters, digits, `-` and `_`.
+fn account_name(account: &str) -> String {
+    account
+        .split(':')
+        .map(|segment| {
+            segment
+                .trim()
+                .chars()
+                .map(|c| {
+                    if c.is_alphanumeric() || c == '_' {
+                        c
+                    } else {
+                        '-'
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// The part before `;`, and the comment after it
+fn split_comment(line: &str) -> (&str, &str) {
+    line.split_once(';').unwrap_or((line, ""))
+}
+
+/// `:food:travel:` tags, or a `key: value` tag, of a comment into `metadata`
+fn tags(comment: &str, metadata: &mut Vec<(String, String)>) {
+    let comment: &str = comment.trim();
+    let mut names: Vec<String> = Vec::new();
+    if comment.len() > 1
+        && comment.starts_with(':')
+        && comment.ends_with(':')
+        && !comment.contains(char::is_whitespace)
+    {
+        names.extend(
+            comment
+                .split(':')
+                .filter(|name| !name.is_empty())
+                .map(ToString::to_string),
+        );
+    } else if let Some((key, value)) = comment.split_once(':')
+        && !key.is_empty()
+        && !key.contains(char::is_whitespace)
+    {
+        let key: String = key
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        match value.trim() {
+            "" => names.push(key),
+            value => metadata.push((key, value.to_string())),
+        }
+    }
+    if names.is_empty() {
+        return;
+    }
+
+    match metadata.iter_mut().find(|(key, _)| key == TAGS) {
+        Some((_, tags)) => {
+            tags.push_str(", ");
+            tags.push_str(&names.join(", "));
+        }
+        None => metadata.push((TAGS.to_string(), names.join(", "))),
+    }
+}
+
+/// The kakei currency of a commodity, and the power of ten of its units in one commodity
+fn commodity(symbol: &str) -> Result<(CurrencyCode, u32), String> {
+    match symbol.trim() {
+        "" | "¥" | "￥" | "円" => Ok((CurrencyCode::JPY, 0)),
+        "₿" => Ok((CurrencyCode::SATS, 8)),
+        symbol => match symbol.to_ascii_uppercase().as_str() {
+            "BTC" | "XBT" => Ok((CurrencyCode::SATS, 8)),
+            "SAT" => Ok((CurrencyCode::SATS, 0)),
+            code => code
+                .parse()
+                .map(|code| (code, 0))
+                .map_err(|_| format!("unsupported commodity: {symbol}")),
+        },
+    }
+}
+
+/// A decimal amount of a commodity as written in a journal
+struct Quantity {
+    mantissa: i128,
+    scale: u32,
+    code: CurrencyCode,
+
+    /// Power of ten of kakei units in one unit of the commodity
+    exponent: u32,
+}
+
+impl Quantity {
+    /// `-¥1,200`, `1,200 JPY`, `JPY -1200`, `1200円` or `0.001 BTC`
+    fn parse(text: &str) -> Result<Self, String> {
+        let numeric = |c: &char| c.is_ascii_digit() || matches!(c, '.' | ',' | '-' | '+');
+        let number: String = text.chars().filter(numeric).filter(|c| *c != ',').collect();
+        let symbol: String = text.chars().filter(|c| !numeric(c)).collect();
+        let (code, exponent) = commodity(symbol.trim().trim_matches('"'))?;
+
+        let (integer, fraction) = number.split_once('.').unwrap_or((&number, ""));
+        let mantissa: i128 = format!("{integer}{fraction}")
+            .parse()
+            .map_err(|_| format!("invalid amount: {}", text.trim()))?;
+        Ok(Self {
+            mantissa,
+            scale: fraction.len() as u32,
+            code,
+            exponent,
+        })
+    }
+
+    /// In kakei units, which have no fraction
+    fn value(&self) -> Result<i64, String> {
+        let units: i128 = self.mantissa * 10i128.pow(self.exponent);
+        let divisor: i128 = 10i128.pow(self.scale);
+        if units % divisor != 0 {
+            return Err(format!(
+                "{} has a fraction of a {}",
+                decimal(self.mantissa, self.scale),
+                self.code
+            ));
+        }
+
+        Ok((units / divisor) as i64)
+    }
+
+    /// This price of one unit times `units`, unsigned and rounded half away from zero
+    fn times(&self, units: &Quantity) -> i64 {
+        let numerator: i128 = (self.mantissa * units.mantissa).abs() * 10i128.pow(self.exponent);
+        let divisor: i128 = 10i128.pow(self.scale + units.scale);
+
+        ((numerator + divisor / 2) / divisor) as i64
+    }
+}
+
+/// `mantissa` with `scale` decimal places, such as `0.095`
+fn decimal(mantissa: i128, scale: u32) -> String {
+    let digits: String = format!("{:0>width$}", mantissa.abs(), width = scale as usize + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - scale as usize);
+    let sign: &str = if mantissa < 0 { "-" } else { "" };
+    if fraction.is_empty() {
+        format!("{sign}{integer}")
+    } else {
+        format!("{sign}{integer}.{fraction}")
+    }
+}
+
+impl Processor {
+    /// The note as a journal of ledger and hledger.
+    pub fn ledger(&self) -> String {
+        let mut text = String::new();
+
+        let mut accounts: BTreeSet<&str> = BTreeSet::new();
+        for entry in &self.entries {
+            match entry {
+                Entry::Open(v) => {
+                    accounts.insert(&v.account);
+                }
+                Entry::Budget(v) => {
+                    accounts.insert(&v.account);
+                }
+                Entry::Transaction(v) => accounts.extend(v.postings.iter().map(|p| &*p.account)),
+                Entry::Recurring(v) => accounts.extend(v.postings.iter().map(|p| &*p.account)),
+                Entry::Include(_) | Entry::Price(_) => {}
+            }
+        }
+        for account in accounts {
+            let _ = writeln!(text, "account {account}");
+        }
+
+        let mut after_price: bool = false;
+        for (index, entry) in self.entries.iter().enumerate() {
+            match entry {
+                Entry::Price(v) => {
+                    if !after_price {
+                        text.push('\n');
+                    }
+                    let _ = writeln!(text, "P {} {} {} {}", v.date, v.base, v.rate, v.quote);
+                }
+                Entry::Transaction(v) => {
+                    let _ = writeln!(text, "\n{} * {}", v.date, v.payee);
+                    for (key, value) in &v.metadata {
+                        if key == TAGS {
+                            let _ = writeln!(text, "    ; :{}:", value.replace(", ", ":"));
+                        } else {
+                            let _ = writeln!(text, "    ; {key}: {value}");
+                        }
+                    }
+                    for posting in &v.postings {
+                        let _ = writeln!(text, "    {}", self::posting(posting));
+                    }
+                }
+                Entry::Budget(v) => {
+                    // A budget lasts until the next one of the account
+                    let to: String = self.entries[index + 1..]
+                        .iter()
+                        .find_map(|entry| match entry {
+                            Entry::Budget(next) if next.account == v.account => {
+                                Some(format!(" to {}", next.date))
+                            }
+                            _ => None,
+                        })
+                        .unwrap_or_default();
+                    let _ = writeln!(
+                        text,
+                        "\n~ {} from {}{to}  budget\n    ({})  {}",
+                        v.period, v.date, v.account, v.amount
+                    );
+                }
+                Entry::Recurring(v) => {
+                    // `to` is the day after the last occurrence
+                    let to: String = v
+                        .until
+                        .map(|until| format!(" to {}", until.add_days(1)))
+                        .unwrap_or_default();
+                    let _ = writeln!(text, "\n~ {} from {}{to}  {}", v.period, v.start, v.payee);
+                    for posting in &v.postings {
+                        let _ = writeln!(text, "    {}", self::posting(posting));
+                    }
+                }
+                Entry::Include(_) | Entry::Open(_) => {}
+            }
+            after_price = matches!(entry, Entry::Price(_));
+        }
+
+        text
+    }
+}
+
+/// `Assets:Wallet  100000 SATS @@ 1450 JPY`
+fn posting(posting: &Posting) -> String {
+    match (posting.amount, posting.cost) {
+        (Some(amount), Some(cost)) => format!(
+            "{}  {amount} @@ {}",
+            posting.account,
+            Amount::new(cost.code(), cost.value().abs())
+        ),
+        (Some(amount), None) => format!("{}  {amount}", posting.account),
+        (None, _) => posting.account.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TAGS, read_journal};
+    use crate::Processor;
+    use kakei_parser::parse;
+    use kakei_types::{
+        currency::{Amount, JPY, SATS},
+        entry::Entry,
+    };
+
+    #[test]
+    fn journals() -> anyhow::Result<()> {
+        let journal = read_journal(
+            "\
+; migrated from hledger
+account Assets:Checking Account  ; type: A
+commodity ¥1,000
+
+P 2026/03/01 BTC 9500000 JPY
+
+2026/03/14=2026/03/16 * (1024) Matsuya  ; :food:lunch:
+    Expenses:Food            ¥980
+    (Budget:Food)            ¥-980
+    Assets:Checking Account  ; card: visa
+
+2026-03-15 Buy
+    Assets:Wallet  0.001 BTC @ 9,500,000 JPY
+    [Assets:Checking Account]  -9500 JPY = 290,520 JPY
+
+~ monthly
+    Expenses:Rent  85000 JPY
+    Assets:Checking Account
+",
+        )?;
+
+        assert_eq!(journal.opens.len(), 1);
+        assert_eq!(
+            journal.opens[0].to_string(),
+            "2026-03-01 open Assets:Checking-Account"
+        );
+        assert_eq!(
+            journal.prices[0].to_string(),
+            "2026-03-01 price SATS 0.095 JPY"
+        );
+        assert_eq!(
+            journal.transactions[0].to_string(),
+            "2026-03-14 \"Matsuya\"\n  ; code: 1024\n  ; tags: food, lunch\n  ; card: visa\n  Expenses:Food  980 JPY\n  Assets:Checking-Account\n"
+        );
+        assert_eq!(
+            journal.transactions[1].postings[0].amount,
+            Some(Amount::SATS(SATS(100000)))
+        );
+        assert_eq!(
+            journal.transactions[1].postings[0].cost,
+            Some(Amount::JPY(JPY(9500)))
+        );
+        assert_eq!(journal.transactions.len(), 2);
+
+        assert!(read_journal("2026/03/14 Pizza\n    Expenses:Food  12.50 USD\n").is_err());
+        assert!(read_journal("2026/03/14 Pizza\n    Expenses:Food  0.5 JPY\n").is_err());
+        assert!(read_journal("include other.journal\n").is_err());
+
+        // A journal written from a note reads back into the same transactions
+        let processor = Processor::from_entries(
+            journal
+                .transactions
+                .iter()
+                .cloned()
+                .map(Entry::Transaction)
+                .collect(),
+        )?;
+        let written: String = processor.ledger();
+        assert!(written.starts_with("account Assets:Checking-Account\naccount Assets:Wallet\n"));
+        assert!(written.contains("    ; :food:lunch:\n"));
+        assert!(written.contains("    Assets:Wallet  100000 SATS @@ 9500 JPY\n"));
+        let read = read_journal(&written)?;
+        assert_eq!(
+            read.transactions,
+            processor.transactions().cloned().collect::<Vec<_>>()
+        );
+        assert_eq!(read.transactions[0].metadata(TAGS), Some("food, lunch"));
+
+        let processor = Processor::from_entries(parse(
+            "\
+2026-03-01 budget Expenses:Food 60000 JPY
+2026-06-01 budget Expenses:Food 50000 JPY
+~ monthly from 2026-01-25 until 2026-12-25 \"Rent\"
+  Expenses:Rent  85000 JPY
+  Assets:Bank
+",
+        )?)?;
+        let written: String = processor.ledger();
+        assert!(written.contains(
+            "\n~ monthly from 2026-03-01 to 2026-06-01  budget\n    (Expenses:Food)  60000 JPY\n"
+        ));
+        assert!(written.contains(
+            "\n~ monthly from 2026-01-25 to 2026-12-26  Rent\n    Expenses:Rent  85000 JPY\n    Assets:Bank\n"
+        ));
+        Ok(())
+    }
+}
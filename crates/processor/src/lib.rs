@@ -1,22 +1,209 @@
 //! Processor crate
 
+pub mod apps;
+pub mod beancount;
+pub mod budget;
+pub mod cache;
+pub mod chart;
 pub mod configuration;
+pub mod duplicate;
+pub mod encoding;
+pub mod forecast;
+pub mod gains;
+pub mod html;
+pub mod import;
+pub mod ledger;
+pub mod ofx;
+pub mod price;
+pub mod recur;
+pub mod reflect;
+pub mod register;
+pub mod suggest;
 
+use crate::cache::NoteCache;
+use crate::encoding::{DecodeError, Encoding};
+use crate::price::{PriceDatabase, PriceImportError};
+use kakei_parser::ParseError;
 use kakei_types::{
-    currency::{JPY, SATS},
-    query::Query,
+    currency::{Amount, CurrencyCode, JPY, SATS},
+    date::Date,
+    entry::{Entry, Posting, Transaction},
+    query::{Query, Valuation},
+    rate::RateError,
 };
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use tabled::Table;
+use thiserror::Error;
 
+#[derive(Debug, Default)]
 pub struct Processor {
+    entries: Vec<Entry>,
+    files: Vec<PathBuf>,
+    prices: PriceDatabase,
     jpy_queries: Vec<Query<JPY>>,
     sats_queries: Vec<Query<SATS>>,
 }
 
+#[derive(Debug, Error)]
+pub enum ProcessorError {
+    #[error("failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("failed to read {path}: {source}")]
+    Decode { path: PathBuf, source: DecodeError },
+
+    #[error("{path}:{source}")]
+    Parse { path: PathBuf, source: ParseError },
+
+    #[error("{date} \"{payee}\": transaction does not balance by {residual}")]
+    Unbalanced {
+        date: Date,
+        payee: String,
+        residual: Amount,
+    },
+
+    #[error("{date} \"{payee}\": only one posting may leave out its amount")]
+    AmbiguousPosting { date: Date, payee: String },
+
+    #[error("{path}: {source}")]
+    PriceImport {
+        path: PathBuf,
+        source: PriceImportError,
+    },
+
+    #[error("no price of {base} in {quote} on or before {date}")]
+    MissingPrice {
+        base: CurrencyCode,
+        quote: CurrencyCode,
+        date: Date,
+    },
+
+    #[error(transparent)]
+    Rate(#[from] RateError),
+
+    #[error("no account matches \"{0}\"")]
+    UnknownAccount(String),
+
+    #[error("cannot infer the accounts of \"{0}\", write them after the amount")]
+    NoAccount(String),
+}
+
 impl Processor {
-    pub fn read(paths: Vec<PathBuf>) {
-        todo!()
+    pub fn read(paths: Vec<PathBuf>) -> Result<Self, ProcessorError> {
+        NoteCache::default().read(paths)
+    }
+
+    /// Same as [`Processor::read`], decoding every file as `encoding` instead of detecting it.
+    pub fn read_encoded(paths: Vec<PathBuf>, encoding: Encoding) -> Result<Self, ProcessorError> {
+        NoteCache::new(Some(encoding)).read(paths)
+    }
+
+    /// The notes at `paths` and every file they include, decoded as `encoding` or the detected
+    /// one, without processing the entries.
+    pub fn note_files(
+        paths: Vec<PathBuf>,
+        encoding: Option<Encoding>,
+    ) -> Result<Vec<PathBuf>, ProcessorError> {
+        let mut entries: Vec<Entry> = Vec::new();
+        let mut files: Vec<PathBuf> = Vec::new();
+
+        let mut cache = NoteCache::new(encoding);
+
+        for path in paths {
+            read_file(path, &mut entries, &mut files, &mut cache)?;
+        }
+
+        Ok(files)
+    }
+
+    /// Builds the balances and the price database from parsed entries.
+    pub fn from_entries(mut entries: Vec<Entry>) -> Result<Self, ProcessorError> {
+        entries.retain(|entry| !matches!(entry, Entry::Include(_)));
+        entries.sort_by_key(|entry| entry.date());
+
+        let mut prices = PriceDatabase::default();
+        let mut jpy: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+        let mut sats: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+
+        for entry in entries.iter_mut() {
+            match entry {
+                Entry::Price(price) => {
+                    prices.insert(price);
+                }
+                Entry::Transaction(transaction) => {
+                    balance(transaction)?;
+
+                    for posting in &transaction.postings {
+                        let Some(amount) = posting.amount else {
+                            continue;
+                        };
+                        let totals = match amount.code() {
+                            CurrencyCode::JPY => &mut jpy,
+                            CurrencyCode::SATS => &mut sats,
+                        };
+                        let (debit, credit) = totals.entry(posting.account.clone()).or_default();
+                        if amount.value() >= 0 {
+                            *debit += amount.value();
+                        } else {
+                            *credit -= amount.value();
+                        }
+                    }
+                }
+                Entry::Recurring(recurring) => {
+                    // Catch a template which never balances before it is generated
+                    balance(&mut recurring.transaction(recurring.start))?;
+                }
+                Entry::Include(_) | Entry::Open(_) | Entry::Budget(_) => {}
+            }
+        }
+
+        Ok(Self {
+            entries,
+            files: Vec::new(),
+            prices,
+            jpy_queries: queries(jpy, JPY),
+            sats_queries: queries(sats, SATS),
+        })
+    }
+
+    /// A processor which only knows the entries on or before `date`.
+    pub fn until(&self, date: Date) -> Result<Self, ProcessorError> {
+        let entries = self
+            .entries
+            .iter()
+            .filter(|entry| entry.date() <= Some(date))
+            .cloned()
+            .collect();
+
+        let mut processor = Self::from_entries(entries)?;
+        processor.files = self.files.clone();
+        Ok(processor)
+    }
+
+    /// Every entry ordered by date. Amounts left out in transactions are filled in.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Every transaction ordered by date
+    pub fn transactions(&self) -> impl Iterator<Item = &Transaction> {
+        self.entries.iter().filter_map(|entry| match entry {
+            Entry::Transaction(transaction) => Some(transaction),
+            _ => None,
+        })
+    }
+
+    /// Every file read by [`Processor::read`], including the included ones.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    pub fn prices(&self) -> &PriceDatabase {
+        &self.prices
     }
 
     pub fn tables(&self) -> Vec<tabled::Table> {
@@ -32,13 +219,225 @@ impl Processor {
 
         result
     }
+
+    /// Every balance converted into `quote` with the most recent prices on or before `date`.
+    pub fn valuations(
+        &self,
+        quote: CurrencyCode,
+        date: Date,
+    ) -> Result<Vec<Valuation>, ProcessorError> {
+        self.balances()
+            .map(|(name, balance)| self.valuation(name, balance, quote, date))
+            .collect()
+    }
+
+    /// The balance of every account in each currency
+    fn balances(&self) -> impl Iterator<Item = (&String, Amount)> {
+        self.jpy_queries
+            .iter()
+            .map(|q| (&q.name, Amount::JPY(q.total)))
+            .chain(
+                self.sats_queries
+                    .iter()
+                    .map(|q| (&q.name, Amount::SATS(q.total))),
+            )
+    }
+
+    fn valuation(
+        &self,
+        name: &str,
+        balance: Amount,
+        quote: CurrencyCode,
+        date: Date,
+    ) -> Result<Valuation, ProcessorError> {
+        let value =
+            self.prices
+                .convert(balance, quote, date)?
+                .ok_or(ProcessorError::MissingPrice {
+                    base: balance.code(),
+                    quote,
+                    date,
+                })?;
+
+        Ok(Valuation {
+            name: name.to_string(),
+            balance,
+            value,
+        })
+    }
+
+    pub fn valuation_table(
+        &self,
+        quote: CurrencyCode,
+        date: Date,
+    ) -> Result<Table, ProcessorError> {
+        Ok(Table::new(self.valuations(quote, date)?))
+    }
+
+    /// Sum of every `Assets` and `Liabilities` balance in `quote`.
+    pub fn net_worth(&self, quote: CurrencyCode, date: Date) -> Result<Amount, ProcessorError> {
+        // Only these are valued, so an income or expense without a price does not stop it
+        let total = self
+            .balances()
+            .filter(|(name, _)| is_balance_sheet_account(name))
+            .map(|(name, balance)| {
+                self.valuation(name, balance, quote, date)
+                    .map(|v| v.value.value())
+            })
+            .sum::<Result<i64, ProcessorError>>()?;
+
+        Ok(Amount::new(quote, total))
+    }
+
+    /// Balance of `account` in each currency it holds, after the `index`th of
+    /// [`Processor::transactions`].
+    pub fn running_balance(&self, account: &str, index: usize) -> Vec<Amount> {
+        let mut balances: BTreeMap<CurrencyCode, i64> = BTreeMap::new();
+        let postings = self
+            .transactions()
+            .take(index + 1)
+            .flat_map(|t| &t.postings)
+            .filter(|p| p.account == account);
+        for amount in postings.filter_map(|p| p.amount) {
+            *balances.entry(amount.code()).or_default() += amount.value();
+        }
+
+        balances
+            .into_iter()
+            .map(|(code, value)| Amount::new(code, value))
+            .collect()
+    }
+}
+
+fn read_file(
+    path: PathBuf,
+    entries: &mut Vec<Entry>,
+    files: &mut Vec<PathBuf>,
+    cache: &mut NoteCache,
+) -> Result<(), ProcessorError> {
+    // An include cycle would otherwise never end
+    if files.contains(&path) {
+        return Ok(());
+    }
+
+    let bytes = std::fs::read(&path).map_err(|source| ProcessorError::Io {
+        path: path.clone(),
+        source,
+    })?;
+    let src =
+        encoding::decode(&bytes, cache.encoding).map_err(|source| ProcessorError::Decode {
+            path: path.clone(),
+            source,
+        })?;
+    let parsed = cache.parse(&path, src)?;
+    files.push(path.clone());
+
+    for entry in parsed {
+        match entry {
+            Entry::Include(include) => {
+                let base = path.parent().unwrap_or(Path::new(""));
+                read_file(base.join(include.path), entries, files, cache)?;
+            }
+            entry => entries.push(entry),
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn root_account(name: &str) -> &str {
+    name.split(':').next().unwrap_or(name)
+}
+
+fn is_balance_sheet_account(name: &str) -> bool {
+    matches!(root_account(name), "Assets" | "Liabilities")
+}
+
+pub(crate) fn is_asset_account(name: &str) -> bool {
+    root_account(name) == "Assets"
+}
+
+/// Whether `name` is `parent` or one of its sub-accounts.
+pub(crate) fn is_sub_account(name: &str, parent: &str) -> bool {
+    name.strip_prefix(parent)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(':'))
+}
+
+fn queries<C, F>(totals: BTreeMap<String, (i64, i64)>, currency: F) -> Vec<Query<C>>
+where
+    C: kakei_types::currency::Currency,
+    F: Fn(i64) -> C,
+{
+    totals
+        .into_iter()
+        .map(|(name, (debit, credit))| Query {
+            name,
+            debit: currency(debit),
+            credit: currency(credit),
+            total: currency(debit) - currency(credit),
+        })
+        .collect()
+}
+
+/// Checks that every currency sums to zero, filling in the posting without an amount.
+pub(crate) fn balance(transaction: &mut Transaction) -> Result<(), ProcessorError> {
+    let mut residuals: BTreeMap<CurrencyCode, i64> = BTreeMap::new();
+    for amount in transaction.postings.iter().filter_map(Posting::weight) {
+        *residuals.entry(amount.code()).or_default() += amount.value();
+    }
+    residuals.retain(|_, value| *value != 0);
+
+    let elided: Vec<usize> = transaction
+        .postings
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.amount.is_none())
+        .map(|(i, _)| i)
+        .collect();
+
+    match elided.as_slice() {
+        [] => match residuals.into_iter().next() {
+            Some((code, value)) => Err(ProcessorError::Unbalanced {
+                date: transaction.date,
+                payee: transaction.payee.clone(),
+                residual: Amount::new(code, value),
+            }),
+            None => Ok(()),
+        },
+        [index] => {
+            let account = transaction.postings[*index].account.clone();
+            let mut filled = residuals
+                .into_iter()
+                .map(|(code, value)| Posting {
+                    account: account.clone(),
+                    amount: Some(Amount::new(code, -value)),
+                    cost: None,
+                })
+                .collect::<Vec<Posting>>();
+
+            if filled.is_empty() {
+                // Nothing to balance, so the posting has a zero amount in no currency
+                transaction.postings.remove(*index);
+            } else {
+                let first = filled.remove(0);
+                transaction.postings[*index] = first;
+                transaction.postings.extend(filled);
+            }
+            Ok(())
+        }
+        _ => Err(ProcessorError::AmbiguousPosting {
+            date: transaction.date,
+            payee: transaction.payee.clone(),
+        }),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Processor;
+    use crate::{Processor, ProcessorError};
     use kakei_types::{
-        currency::{JPY, SATS},
+        currency::{Amount, CurrencyCode, JPY, SATS},
+        date::Date,
         query::Query,
     };
     use tabled::{Table, assert::assert_table};
@@ -48,6 +447,7 @@ mod tests {
         let processor = Processor {
             jpy_queries: vec![],
             sats_queries: vec![],
+            ..Default::default()
         };
         let tables: Vec<Table> = processor.tables();
 
@@ -98,6 +498,7 @@ mod tests {
                     total: SATS(0),
                 },
             ],
+            ..Default::default()
         };
         let tables: Vec<Table> = processor.tables();
 
@@ -124,4 +525,83 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn net_worth_in_jpy() -> anyhow::Result<()> {
+        let src = "\
+2026-01-01 open Assets:Bank JPY
+2026-01-01 open Assets:Wallet SATS
+2026-03-01 \"Salary\"
+  Assets:Bank  300,000 JPY
+  Income:Salary
+2026-03-02 \"Zap\"
+  Assets:Wallet  100000 SATS
+  Income:Tips
+2026-03-14 price SATS 0.0145 JPY
+";
+        let processor = Processor::from_entries(kakei_parser::parse(src)?)?;
+        let date = Date::new(2026, 3, 31)?;
+
+        assert_eq!(
+            processor.net_worth(CurrencyCode::JPY, date)?,
+            Amount::JPY(JPY(301_450))
+        );
+        assert!(
+            processor
+                .net_worth(CurrencyCode::JPY, Date::new(2026, 3, 13)?)
+                .is_err()
+        );
+
+        let src = "\
+2026-03-01 \"Salary\"
+  Assets:Bank  300,000 JPY
+  Income:Salary
+2026-03-02 \"Zap\"
+  Expenses:Tips  100 SATS
+  Income:Tips
+";
+        let processor = Processor::from_entries(kakei_parser::parse(src)?)?;
+        assert_eq!(
+            processor.net_worth(CurrencyCode::JPY, date)?,
+            Amount::JPY(JPY(300_000))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn running_balance() -> anyhow::Result<()> {
+        let src = "\
+2026-03-01 \"ATM\"
+  Assets:Wallet  10000 JPY
+  Assets:Bank
+2026-03-02 \"Matsuya\"
+  Expenses:Food  980 JPY
+  Assets:Wallet
+";
+        let processor = Processor::from_entries(kakei_parser::parse(src)?)?;
+
+        assert_eq!(
+            processor.running_balance("Assets:Wallet", 0),
+            vec![Amount::JPY(JPY(10000))]
+        );
+        assert_eq!(
+            processor.running_balance("Assets:Wallet", 1),
+            vec![Amount::JPY(JPY(9020))]
+        );
+        assert!(processor.running_balance("Expenses:Food", 0).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn unbalanced_transaction() -> anyhow::Result<()> {
+        let src = "\
+2026-03-14 \"Matsuya\"
+  Expenses:Food  980 JPY
+  Assets:Wallet  -900 JPY
+";
+        let result = Processor::from_entries(kakei_parser::parse(src)?);
+
+        assert!(matches!(result, Err(ProcessorError::Unbalanced { .. })));
+        Ok(())
+    }
 }
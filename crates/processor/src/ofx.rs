@@ -0,0 +1,246 @@
+//! ----- OFX statements -----
+//!
+//! OFX 1.x is SGML, whose elements holding a value are not closed, and OFX 2.x is XML. Both
+//! write a value right after its tag and close every aggregate, so both are read by looking for
+//! `<STMTTRN>` ... `</STMTTRN>` and the value after each tag in between. QFX is OFX with a few
+//! more elements of Intuit.
+//!
+//! Only statements of bank accounts and credit cards in yen are read. A transaction in another
+//! currency, by `CURDEF` or its own `CURRENCY`, is an error naming it instead of a guessed
+//! conversion, and so is a brokerage statement, whose trades kakei has no entries for.
+
+use crate::import::{ImportError, Rules, Statement, parse_date, statement_line};
+use kakei_types::entry::Transaction;
+use std::collections::BTreeMap;
+
+/// Metadata key of the FITID, the ID the institution gives each transaction
+pub const FITID: &str = "fitid";
+
+/// Statements of bank accounts and credit cards
+const STATEMENTS: [&str; 2] = ["STMTRS", "CCSTMTRS"];
+
+/// Reads every statement of an OFX or QFX file, finding the kakei account of each by its
+/// `ACCTID` in `accounts`.
+pub fn read_ofx(
+    text: &str,
+    accounts: &BTreeMap<String, String>,
+    rules: &Rules,
+) -> Result<Vec<Statement>, ImportError> {
+    if text.contains("<INVSTMTRS>") {
+        return Err(ImportError::Ofx(
+            "investment statements (INVSTMTRS) are not supported".to_string(),
+        ));
+    }
+
+    let mut statements: Vec<Statement> = Vec::new();
+    for statement in STATEMENTS.iter().flat_map(|tag| elements(text, tag)) {
+        let id: String = value(statement, "ACCTID")
+            .ok_or_else(|| ImportError::Ofx("a statement has no ACCTID".to_string()))?;
+        let account: &String = accounts
+            .get(&id)
+            .ok_or_else(|| ImportError::UnknownAccount(id.clone()))?;
+        let default: Option<String> = value(statement, "CURDEF");
+
+        let transactions = elements(statement, "STMTTRN")
+            .into_iter()
+            .map(|transaction| read_transaction(transaction, default.as_deref(), account, rules))
+            .collect::<Result<Vec<Transaction>, ImportError>>()?;
+        statements.push(Statement {
+            account: account.clone(),
+            transactions,
+        });
+    }
+
+    if statements.is_empty() {
+        return Err(ImportError::Ofx("no statement in the file".to_string()));
+    }
+    Ok(statements)
+}
+
+fn read_transaction(
+    transaction: &str,
+    default: Option<&str>,
+    account: &str,
+    rules: &Rules,
+) -> Result<Transaction, ImportError> {
+    let required = |tag: &str| {
+        value(transaction, tag)
+            .ok_or_else(|| ImportError::Ofx(format!("a transaction has no {tag}")))
+    };
+
+    // `20260314120000.000[+9:JST]`, of which only the day is kept
+    let posted: String = required("DTPOSTED")?;
+    let date = posted
+        .get(..8)
+        .and_then(|day| parse_date(day, "%Y%m%d"))
+        .ok_or_else(|| ImportError::Ofx(format!("invalid DTPOSTED: {posted}")))?;
+    let fitid: Option<String> = value(transaction, "FITID");
+
+    // `<CURRENCY>` when the amount is in another currency than `CURDEF`, whereas an amount with
+    // `<ORIGCURRENCY>` is already converted to `CURDEF`
+    let currency: String = elements(transaction, "CURRENCY")
+        .first()
+        .and_then(|currency| value(currency, "CURSYM"))
+        .or_else(|| default.map(str::to_string))
+        .unwrap_or_else(|| "JPY".to_string());
+    if currency != "JPY" {
+        return Err(ImportError::OfxCurrency {
+            transaction: fitid.unwrap_or(posted),
+            currency,
+        });
+    }
+
+    let amount: String = required("TRNAMT")?;
+    let change: i64 = parse_yen(&amount)
+        .ok_or_else(|| ImportError::Ofx(format!("invalid TRNAMT of yen: {amount}")))?;
+    let payee: String = value(transaction, "NAME")
+        .or_else(|| value(transaction, "MEMO"))
+        .unwrap_or_default();
+    let metadata: Vec<(String, String)> = fitid
+        .map(|fitid| vec![(FITID.to_string(), fitid)])
+        .unwrap_or_default();
+
+    Ok(statement_line(
+        date, &payee, change, account, rules, metadata,
+    ))
+}
+
+/// The contents of every `<tag>` ... `</tag>` in `text`
+fn elements<'a>(text: &'a str, tag: &str) -> Vec<&'a str> {
+    let (open, close) = (format!("<{tag}>"), format!("</{tag}>"));
+    let mut found: Vec<&str> = Vec::new();
+    let mut rest: &str = text;
+    while let Some(start) = rest.find(&open) {
+        let inner: &str = &rest[start + open.len()..];
+        let Some(end) = inner.find(&close) else {
+            break;
+        };
+        found.push(&inner[..end]);
+        rest = &inner[end + close.len()..];
+    }
+
+    found
+}
+
+/// The value after the first `<tag>` in `text`, up to the next tag
+fn value(text: &str, tag: &str) -> Option<String> {
+    let open = format!("<{tag}>");
+    let start: usize = text.find(&open)? + open.len();
+    let value: &str = text[start..].split('<').next()?.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    Some(
+        value
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&"),
+    )
+}
+
+/// `-1234.00` as -1234. Yen has no fraction, so a fraction other than zero is invalid.
+fn parse_yen(text: &str) -> Option<i64> {
+    let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+    if !fraction.chars().all(|c| c == '0') {
+        return None;
+    }
+
+    whole.trim_start_matches('+').parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FITID, read_ofx};
+    use crate::import::{ImportError, Rules};
+    use kakei_types::currency::{Amount, JPY};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn statements() -> anyhow::Result<()> {
+        let sgml = "\
+OFXHEADER:100
+DATA:OFXSGML
+VERSION:102
+
+<OFX>
+<CREDITCARDMSGSRSV1><CCSTMTTRNRS><CCSTMTRS>
+<CURDEF>JPY
+<CCACCTFROM><ACCTID>4980-0000</CCACCTFROM>
+<BANKTRANLIST>
+<STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20260314120000.000[+9:JST]<TRNAMT>-980.00<FITID>T001<NAME>MATSUYA &amp; CO</STMTTRN>
+<STMTTRN><TRNTYPE>CREDIT<DTPOSTED>20260320<TRNAMT>500<FITID>T002<MEMO>REFUND</STMTTRN>
+</BANKTRANLIST>
+</CCSTMTRS></CCSTMTTRNRS></CREDITCARDMSGSRSV1>
+</OFX>
+";
+        let xml = "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<?OFX OFXHEADER=\"200\" VERSION=\"220\"?>
+<OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS>
+<CURDEF>JPY</CURDEF>
+<BANKACCTFROM><BANKID>0001</BANKID><ACCTID>1234567</ACCTID></BANKACCTFROM>
+<BANKTRANLIST>
+<STMTTRN><TRNTYPE>DEP</TRNTYPE><DTPOSTED>20260325</DTPOSTED><TRNAMT>300000</TRNAMT><FITID>B9</FITID><NAME>給与</NAME></STMTTRN>
+</BANKTRANLIST>
+</STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>
+";
+        let accounts: BTreeMap<String, String> = BTreeMap::from([
+            ("4980-0000".to_string(), "Liabilities:Card".to_string()),
+            ("1234567".to_string(), "Assets:Bank".to_string()),
+        ]);
+        let rules = Rules::parse("[[rule]]\npayee = \"給与\"\naccount = \"Income:Salary\"\n")?;
+
+        let card = &read_ofx(sgml, &accounts, &rules)?[0];
+        assert_eq!(card.account, "Liabilities:Card");
+        assert_eq!(
+            card.transactions[0].to_string(),
+            "2026-03-14 \"MATSUYA & CO\"\n  ; fitid: T001\n  Expenses:Uncategorized  980 JPY\n  Liabilities:Card\n"
+        );
+        assert_eq!(card.transactions[1].payee, "REFUND");
+        assert_eq!(card.transactions[1].metadata(FITID), Some("T002"));
+
+        let bank = &read_ofx(xml, &accounts, &rules)?[0];
+        assert_eq!(bank.transactions[0].postings[0].account, "Income:Salary");
+        assert_eq!(
+            bank.transactions[0].postings[0].amount,
+            Some(Amount::JPY(JPY(-300000)))
+        );
+
+        assert!(matches!(
+            read_ofx(xml, &BTreeMap::new(), &rules),
+            Err(ImportError::UnknownAccount(id)) if id == "1234567"
+        ));
+        assert!(matches!(
+            read_ofx(&xml.replace("<CURDEF>JPY", "<CURDEF>USD"), &accounts, &rules),
+            Err(ImportError::OfxCurrency { transaction, currency })
+                if transaction == "B9" && currency == "USD"
+        ));
+        let euro: String = sgml.replace(
+            "<FITID>T002",
+            "<CURRENCY><CURRATE>160.5<CURSYM>EUR</CURRENCY><FITID>T002",
+        );
+        assert!(matches!(
+            read_ofx(&euro, &accounts, &rules),
+            Err(ImportError::OfxCurrency { transaction, currency })
+                if transaction == "T002" && currency == "EUR"
+        ));
+        let converted: String = sgml.replace(
+            "<FITID>T002",
+            "<ORIGCURRENCY><CURRATE>160.5<CURSYM>EUR</ORIGCURRENCY><FITID>T002",
+        );
+        assert_eq!(
+            read_ofx(&converted, &accounts, &rules)?[0]
+                .transactions
+                .len(),
+            2
+        );
+        assert!(matches!(
+            read_ofx(&xml.replace("STMTRS>", "INVSTMTRS>"), &accounts, &rules),
+            Err(ImportError::Ofx(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+//! ----- Price database -----
+
+use crate::ProcessorError;
+use kakei_types::{
+    currency::{Amount, CurrencyCode, UnknownCurrency},
+    date::Date,
+    entry::Price,
+    rate::{Rate, RateError},
+};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+use thiserror::Error;
+
+/// Exchange rates per currency pair, keyed by date.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PriceDatabase {
+    prices: BTreeMap<(CurrencyCode, CurrencyCode), BTreeMap<Date, Rate>>,
+}
+
+impl PriceDatabase {
+    /// Stores a price. A later price for the same pair and date replaces the earlier one.
+    ///
+    /// Returns `false` when the same rate was already stored.
+    pub fn insert(&mut self, price: &Price) -> bool {
+        self.prices
+            .entry((price.base, price.quote))
+            .or_default()
+            .insert(price.date, price.rate)
+            != Some(price.rate)
+    }
+
+    /// The most recent price of `base` in `quote` on or before `date`.
+    pub fn latest(&self, base: CurrencyCode, quote: CurrencyCode, date: Date) -> Option<Price> {
+        let (&date, &rate) = self
+            .prices
+            .get(&(base, quote))?
+            .range(..=date)
+            .next_back()?;
+
+        Some(Price {
+            date,
+            base,
+            rate,
+            quote,
+        })
+    }
+
+    /// Converts `amount` into `quote` with the most recent price on or before `date`.
+    ///
+    /// A price written in the opposite direction is used inversely when needed. `None` when no
+    /// price is known.
+    pub fn convert(
+        &self,
+        amount: Amount,
+        quote: CurrencyCode,
+        date: Date,
+    ) -> Result<Option<Amount>, RateError> {
+        let base = amount.code();
+        if base == quote {
+            return Ok(Some(amount));
+        }
+
+        let direct = self.latest(base, quote, date);
+        let inverse = self.latest(quote, base, date);
+        let value = match (direct, inverse) {
+            (Some(d), Some(i)) if i.date > d.date => i.rate.convert_inverse(amount.value())?,
+            (Some(d), _) => d.rate.convert(amount.value())?,
+            (None, Some(i)) => i.rate.convert_inverse(amount.value())?,
+            (None, None) => return Ok(None),
+        };
+
+        Ok(Some(Amount::new(quote, value)))
+    }
+
+    /// Every stored price, ordered by currency pair and date.
+    pub fn iter(&self) -> impl Iterator<Item = Price> + '_ {
+        self.prices.iter().flat_map(|(&(base, quote), rates)| {
+            rates.iter().map(move |(&date, &rate)| Price {
+                date,
+                base,
+                rate,
+                quote,
+            })
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prices.is_empty()
+    }
+
+    /// Reads a `date,base,quote,rate` CSV file into the database, such as that of the price file
+    /// being written.
+    ///
+    /// Prices already stored with the same rate are skipped as duplicates.
+    pub fn import_csv(&mut self, path: &Path) -> Result<PriceImport, ProcessorError> {
+        let file = std::fs::File::open(path).map_err(|source| ProcessorError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let prices = read_csv(file).map_err(|source| ProcessorError::PriceImport {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let mut result = PriceImport::default();
+        for price in prices {
+            if self.insert(&price) {
+                result.imported.push(price);
+            } else {
+                result.duplicates += 1;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Canonical price file, one `price` directive per line ordered by date.
+    pub fn to_note(&self) -> String {
+        let mut prices: Vec<Price> = self.iter().collect();
+        prices.sort_by_key(|p| (p.date, p.base, p.quote));
+
+        prices.iter().map(|p| format!("{p}\n")).collect()
+    }
+}
+
+/// Result of [`PriceDatabase::import_csv`]
+#[derive(Debug, Default)]
+pub struct PriceImport {
+    pub imported: Vec<Price>,
+    pub duplicates: usize,
+}
+
+#[derive(Debug, Error)]
+pub enum PriceImportError {
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+
+    #[error("line {line}: expected date,base,quote,rate but got {fields} fields")]
+    Fields { line: u64, fields: usize },
+
+    #[error("line {line}: {message}")]
+    Invalid { line: u64, message: String },
+}
+
+/// Reads `date,base,quote,rate` records, such as `2026-03-14,SATS,JPY,0.0145`.
+///
+/// A header line is skipped, and dates may also be written as `2026/03/14`.
+pub fn read_csv<R: Read>(reader: R) -> Result<Vec<Price>, PriceImportError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .trim(csv::Trim::All)
+        .comment(Some(b'#'))
+        .flexible(true)
+        .from_reader(reader);
+    let mut prices: Vec<Price> = Vec::new();
+
+    for (index, record) in reader.records().enumerate() {
+        let record = record?;
+        let line = record.position().map_or(0, |p| p.line());
+        let invalid = |message: String| PriceImportError::Invalid { line, message };
+
+        let [date, base, quote, rate] = record.iter().collect::<Vec<&str>>()[..] else {
+            return Err(PriceImportError::Fields {
+                line,
+                fields: record.len(),
+            });
+        };
+        let date: Date = match date.replace('/', "-").parse() {
+            Ok(date) => date,
+            Err(_) if index == 0 => continue,
+            Err(e) => return Err(invalid(e.to_string())),
+        };
+
+        prices.push(Price {
+            date,
+            base: base
+                .parse()
+                .map_err(|e: UnknownCurrency| invalid(e.to_string()))?,
+            quote: quote
+                .parse()
+                .map_err(|e: UnknownCurrency| invalid(e.to_string()))?,
+            rate: rate
+                .parse()
+                .map_err(|e: RateError| invalid(e.to_string()))?,
+        });
+    }
+
+    Ok(prices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PriceDatabase;
+    use kakei_types::{
+        currency::{Amount, CurrencyCode, JPY, SATS},
+        date::Date,
+        entry::Price,
+    };
+
+    #[test]
+    fn convert_with_latest_price() -> anyhow::Result<()> {
+        let mut prices = PriceDatabase::default();
+        for (date, rate) in [("2026-03-01", "0.0140"), ("2026-03-14", "0.0145")] {
+            prices.insert(&Price {
+                date: date.parse()?,
+                base: CurrencyCode::SATS,
+                rate: rate.parse()?,
+                quote: CurrencyCode::JPY,
+            });
+        }
+        let sats = Amount::SATS(SATS(100_000));
+
+        assert_eq!(
+            prices.convert(sats, CurrencyCode::JPY, Date::new(2026, 3, 13)?)?,
+            Some(Amount::JPY(JPY(1400)))
+        );
+        assert_eq!(
+            prices.convert(sats, CurrencyCode::JPY, Date::new(2026, 12, 31)?)?,
+            Some(Amount::JPY(JPY(1450)))
+        );
+        assert_eq!(
+            prices.convert(
+                Amount::JPY(JPY(1450)),
+                CurrencyCode::SATS,
+                Date::new(2026, 3, 14)?
+            )?,
+            Some(sats)
+        );
+        assert_eq!(
+            prices.convert(sats, CurrencyCode::JPY, Date::new(2026, 2, 28)?)?,
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn import_csv() -> anyhow::Result<()> {
+        let src = "\
+date,base,quote,rate
+2026/03/14,SATS,JPY,0.0145
+2026-03-01, SATS, JPY, 0.0140
+2026-03-14,SATS,JPY,0.01450
+";
+        let mut prices = PriceDatabase::default();
+        let inserted: Vec<bool> = super::read_csv(src.as_bytes())?
+            .iter()
+            .map(|p| prices.insert(p))
+            .collect();
+
+        assert_eq!(inserted, vec![true, true, false]);
+        assert_eq!(
+            prices.to_note(),
+            "2026-03-01 price SATS 0.014 JPY\n2026-03-14 price SATS 0.0145 JPY\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn import_csv_error() {
+        let src = "2026-03-14,SATS,JPY,0.0145\n2026-03-15,SATS,USD,150\n";
+
+        match super::read_csv(src.as_bytes()) {
+            Err(super::PriceImportError::Invalid { line, .. }) => assert_eq!(line, 2),
+            v => panic!("unexpected result: {v:?}"),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! ----- Recurring transactions -----
+
+use crate::{Processor, ProcessorError, balance};
+use kakei_types::{
+    date::Date,
+    entry::{Entry, Recurring, Transaction},
+};
+use std::ops::Range;
+
+impl Processor {
+    /// Every recurring template in the notes
+    pub fn recurring(&self) -> impl Iterator<Item = &Recurring> {
+        self.entries.iter().filter_map(|entry| match entry {
+            Entry::Recurring(recurring) => Some(recurring),
+            _ => None,
+        })
+    }
+
+    /// Transactions generated from recurring templates in `range`, ordered by date.
+    ///
+    /// An occurrence is skipped when a transaction with the same payee is already written in the
+    /// note within the period of the occurrence, such as one written by `kakei recur materialize`
+    /// or a rent paid a few days late.
+    pub fn generate(&self, range: Range<Date>) -> Result<Vec<Transaction>, ProcessorError> {
+        let mut result: Vec<Transaction> = Vec::new();
+
+        for recurring in self.recurring() {
+            for date in recurring.occurrences(range.clone()) {
+                let period: Range<Date> = recurring.period.containing(recurring.start, date);
+                let written = self.transactions().any(|t| {
+                    !t.generated && t.payee == recurring.payee && period.contains(&t.date)
+                });
+                if written {
+                    continue;
+                }
+
+                let mut transaction = recurring.transaction(date);
+                balance(&mut transaction)?;
+                result.push(transaction);
+            }
+        }
+
+        result.sort_by_key(|t| t.date);
+        Ok(result)
+    }
+
+    /// Occurrences on or before `until` which are not written in the note yet.
+    pub fn pending(&self, until: Date) -> Result<Vec<Transaction>, ProcessorError> {
+        let Some(start) = self.recurring().map(|r| r.start).min() else {
+            return Ok(Vec::new());
+        };
+
+        self.generate(start..until.add_days(1))
+    }
+
+    /// A processor which also knows the transactions generated in `range`.
+    pub fn with_generated(&self, range: Range<Date>) -> Result<Self, ProcessorError> {
+        let mut entries: Vec<Entry> = self.entries.clone();
+        entries.extend(self.generate(range)?.into_iter().map(Entry::Transaction));
+
+        let mut processor = Self::from_entries(entries)?;
+        processor.files = self.files.clone();
+        Ok(processor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Processor;
+    use kakei_types::{
+        currency::{Amount, JPY},
+        date::Date,
+    };
+
+    #[test]
+    fn generate_skips_written_occurrences() -> anyhow::Result<()> {
+        let src = "\
+~ monthly from 2026-01-31 \"Rent\"
+  Expenses:Rent  85000 JPY
+  Assets:Bank
+2026-01-31 \"Rent\"
+  Expenses:Rent  85000 JPY
+  Assets:Bank
+";
+        let processor = Processor::from_entries(kakei_parser::parse(src)?)?;
+        let generated = processor.pending(Date::new(2026, 4, 15)?)?;
+
+        let dates: Vec<Date> = generated.iter().map(|t| t.date).collect();
+        assert_eq!(
+            dates,
+            vec![Date::new(2026, 2, 28)?, Date::new(2026, 3, 31)?]
+        );
+        assert!(generated.iter().all(|t| t.generated));
+        assert_eq!(
+            generated[0].postings[1].amount,
+            Some(Amount::JPY(JPY(-85000)))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn generate_skips_occurrences_written_in_their_period() -> anyhow::Result<()> {
+        let src = "\
+~ monthly from 2026-01-25 \"Rent\"
+  Expenses:Rent  85000 JPY
+  Assets:Bank
+2026-02-27 \"Rent\"
+  Expenses:Rent  85000 JPY
+  Assets:Bank
+";
+        let processor = Processor::from_entries(kakei_parser::parse(src)?)?;
+        let generated = processor.pending(Date::new(2026, 3, 31)?)?;
+
+        let dates: Vec<Date> = generated.iter().map(|t| t.date).collect();
+        assert_eq!(
+            dates,
+            vec![Date::new(2026, 1, 25)?, Date::new(2026, 3, 25)?]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+//! ----- Kakeibo reflection -----
+//!
+//! The kakeibo method asks four questions each month: how much came in, how much to save,
+//! how much was spent, and how to improve.
+
+use crate::{Processor, configuration::Configuration, is_sub_account, root_account};
+use kakei_types::{
+    currency::{CurrencyCode, JPY},
+    date::{Date, Period},
+    query::{Percent, ReflectionQuery},
+};
+use tabled::Table;
+
+/// The four pillars of kakeibo spending
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Pillar {
+    Needs,
+    Wants,
+    Culture,
+    Unexpected,
+}
+
+impl Pillar {
+    pub const ALL: [Pillar; 4] = [Self::Needs, Self::Wants, Self::Culture, Self::Unexpected];
+}
+
+impl std::fmt::Display for Pillar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Needs => write!(f, "Needs"),
+            Self::Wants => write!(f, "Wants"),
+            Self::Culture => write!(f, "Culture"),
+            Self::Unexpected => write!(f, "Unexpected"),
+        }
+    }
+}
+
+/// Income and spending of one month in JPY
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MonthSummary {
+    pub income: i64,
+    pub fixed: i64,
+
+    /// Variable spending in the order of [`Pillar::ALL`]
+    pub pillars: [i64; 4],
+
+    /// Variable spending in no pillar
+    pub unclassified: i64,
+}
+
+impl MonthSummary {
+    pub fn pillar(&self, pillar: Pillar) -> JPY {
+        JPY(self.pillars[pillar as usize])
+    }
+
+    pub fn spending(&self) -> JPY {
+        JPY(self.fixed + self.pillars.iter().sum::<i64>() + self.unclassified)
+    }
+
+    pub fn savings(&self) -> JPY {
+        JPY(self.income) - self.spending()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reflection {
+    /// First day of the month
+    pub month: Date,
+    pub this_month: MonthSummary,
+    pub last_month: MonthSummary,
+    pub goal: Option<JPY>,
+}
+
+impl Reflection {
+    pub fn queries(&self) -> Vec<ReflectionQuery> {
+        let query = |name: String, this_month: JPY, last_month: JPY| ReflectionQuery {
+            name,
+            this_month,
+            last_month,
+            change: this_month - last_month,
+        };
+        let (this, last) = (&self.this_month, &self.last_month);
+
+        let mut result = vec![
+            query("Income".to_string(), JPY(this.income), JPY(last.income)),
+            query("Fixed costs".to_string(), JPY(this.fixed), JPY(last.fixed)),
+        ];
+        for pillar in Pillar::ALL {
+            result.push(query(
+                pillar.to_string(),
+                this.pillar(pillar),
+                last.pillar(pillar),
+            ));
+        }
+        if this.unclassified != 0 || last.unclassified != 0 {
+            result.push(query(
+                "Unclassified".to_string(),
+                JPY(this.unclassified),
+                JPY(last.unclassified),
+            ));
+        }
+        result.push(query("Savings".to_string(), this.savings(), last.savings()));
+
+        result
+    }
+
+    pub fn table(&self) -> Table {
+        Table::new(self.queries())
+    }
+
+    /// Savings against the goal
+    pub fn achieved(&self) -> Option<Percent> {
+        self.goal
+            .and_then(|goal| Percent::of(self.this_month.savings().0, goal.0))
+    }
+
+    /// The spending which grew the most from last month, a hint for how to improve
+    pub fn largest_increase(&self) -> Option<ReflectionQuery> {
+        self.queries()
+            .into_iter()
+            .filter(|q| q.name != "Income" && q.name != "Savings" && q.change.0 > 0)
+            .max_by_key(|q| q.change.0)
+    }
+}
+
+impl Processor {
+    /// Kakeibo reflection of the month containing `date`.
+    pub fn reflection(&self, date: Date, configuration: &Configuration) -> Reflection {
+        let month = Date::new(date.year(), date.month(), 1).unwrap_or(date);
+
+        Reflection {
+            month,
+            this_month: self.month_summary(month, configuration),
+            last_month: self.month_summary(month.add_months(-1), configuration),
+            goal: configuration.savings_goal.map(JPY),
+        }
+    }
+
+    fn month_summary(&self, month: Date, configuration: &Configuration) -> MonthSummary {
+        let range = Period::Monthly.containing(month, month);
+        let kakeibo = &configuration.kakeibo;
+        let categories: [&Vec<String>; 4] = [
+            &kakeibo.needs,
+            &kakeibo.wants,
+            &kakeibo.culture,
+            &kakeibo.unexpected,
+        ];
+        let mut summary = MonthSummary::default();
+
+        let postings = self
+            .transactions()
+            .filter(|t| range.contains(&t.date))
+            .flat_map(|t| &t.postings);
+        for posting in postings {
+            let Some(weight) = posting.weight().filter(|w| w.code() == CurrencyCode::JPY) else {
+                continue;
+            };
+
+            match root_account(&posting.account) {
+                "Income" => summary.income -= weight.value(),
+                "Expenses" => {
+                    // The most specific account in the configuration decides the category
+                    let matched = |accounts: &Vec<String>| {
+                        accounts
+                            .iter()
+                            .filter(|a| is_sub_account(&posting.account, a))
+                            .map(String::len)
+                            .max()
+                    };
+                    let fixed = matched(&kakeibo.fixed);
+                    let pillar = categories
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(i, accounts)| matched(accounts).map(|len| (len, i)))
+                        .max();
+
+                    match (fixed, pillar) {
+                        (Some(f), Some((p, _))) if f >= p => summary.fixed += weight.value(),
+                        (Some(_), None) => summary.fixed += weight.value(),
+                        (_, Some((_, i))) => summary.pillars[i] += weight.value(),
+                        (None, None) => summary.unclassified += weight.value(),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pillar;
+    use crate::{
+        Processor,
+        configuration::{Configuration, KakeiboConfiguration},
+    };
+    use kakei_types::{currency::JPY, date::Date};
+
+    #[test]
+    fn reflection() -> anyhow::Result<()> {
+        let src = "\
+2026-03-25 \"Salary\"
+  Assets:Bank  300000 JPY
+  Income:Salary
+2026-04-25 \"Salary\"
+  Assets:Bank  300000 JPY
+  Income:Salary
+2026-04-27 \"Rent\"
+  Expenses:Housing:Rent  85000 JPY
+  Assets:Bank
+2026-04-28 \"Supermarket\"
+  Expenses:Food  20000 JPY
+  Expenses:Housing:Repair  10000 JPY
+  Expenses:Hobby  5000 JPY
+  Assets:Bank
+";
+        let configuration = Configuration {
+            savings_goal: Some(100_000),
+            kakeibo: KakeiboConfiguration {
+                fixed: vec!["Expenses:Housing:Rent".to_string()],
+                needs: vec!["Expenses:Food".to_string()],
+                unexpected: vec!["Expenses:Housing".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let processor = Processor::from_entries(kakei_parser::parse(src)?)?;
+        let reflection = processor.reflection(Date::new(2026, 4, 15)?, &configuration);
+
+        assert_eq!(reflection.month, Date::new(2026, 4, 1)?);
+        assert_eq!(reflection.this_month.income, 300_000);
+        assert_eq!(reflection.this_month.fixed, 85_000);
+        assert_eq!(reflection.this_month.pillar(Pillar::Needs), JPY(20_000));
+        assert_eq!(
+            reflection.this_month.pillar(Pillar::Unexpected),
+            JPY(10_000)
+        );
+        assert_eq!(reflection.this_month.unclassified, 5_000);
+        assert_eq!(reflection.this_month.savings(), JPY(180_000));
+        assert_eq!(
+            reflection.achieved().map(|p| p.to_string()),
+            Some("180.0%".to_string())
+        );
+        assert_eq!(
+            reflection.largest_increase().map(|q| q.name),
+            Some("Fixed costs".to_string())
+        );
+        Ok(())
+    }
+}
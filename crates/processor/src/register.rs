@@ -0,0 +1,192 @@
+//! ----- Register and account tree for browsing the notes -----
+
+use crate::{Processor, is_balance_sheet_account, is_sub_account};
+use kakei_types::{
+    currency::{Amount, CurrencyCode},
+    date::Date,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+
+/// One posting in [`Processor::register`]
+#[derive(Debug, PartialEq, Clone)]
+pub struct RegisterRow {
+    pub date: Date,
+    pub payee: String,
+
+    /// The account posted to, which may be a sub-account of the registered one
+    pub account: String,
+
+    /// The other accounts of the transaction
+    pub counterparts: Vec<String>,
+    pub amount: Amount,
+
+    /// Balance in the currency of `amount` after this posting
+    pub balance: Amount,
+}
+
+/// One account in [`Processor::account_tree`]
+#[derive(Debug, PartialEq, Clone)]
+pub struct AccountNode {
+    pub name: String,
+
+    /// Number of parent accounts, 0 for `Assets`
+    pub depth: usize,
+
+    /// Sub-accounts included. `Assets` and `Liabilities` show their balance at the end of the
+    /// period, the others what they changed by during it.
+    pub totals: Vec<Amount>,
+}
+
+impl Processor {
+    /// Postings to `account` and its sub-accounts dated in `range`, with the balance counted
+    /// from the first transaction.
+    pub fn register(&self, account: &str, range: Range<Date>) -> Vec<RegisterRow> {
+        let mut balances: BTreeMap<CurrencyCode, i64> = BTreeMap::new();
+        let mut rows: Vec<RegisterRow> = Vec::new();
+
+        for transaction in self.transactions() {
+            if transaction.date >= range.end {
+                break;
+            }
+
+            let postings = transaction
+                .postings
+                .iter()
+                .filter(|p| is_sub_account(&p.account, account));
+            for posting in postings {
+                let Some(amount) = posting.amount else {
+                    continue;
+                };
+                let balance = balances.entry(amount.code()).or_default();
+                *balance += amount.value();
+
+                if transaction.date >= range.start {
+                    rows.push(RegisterRow {
+                        date: transaction.date,
+                        payee: transaction.payee.clone(),
+                        account: posting.account.clone(),
+                        counterparts: transaction
+                            .postings
+                            .iter()
+                            .filter(|p| !is_sub_account(&p.account, account))
+                            .map(|p| p.account.clone())
+                            .collect(),
+                        amount,
+                        balance: Amount::new(amount.code(), *balance),
+                    });
+                }
+            }
+        }
+
+        rows
+    }
+
+    /// Every account with its parents, parents first, for the period `range`.
+    pub fn account_tree(&self, range: Range<Date>) -> Vec<AccountNode> {
+        let mut names: BTreeSet<&str> = BTreeSet::new();
+        for account in self.accounts() {
+            names.extend(
+                account
+                    .match_indices(':')
+                    .map(|(index, _)| &account[..index]),
+            );
+            names.insert(account);
+        }
+
+        // Sorting by segment keeps `Assets:Bank:Savings` right after `Assets:Bank`
+        let mut names: Vec<&str> = names.into_iter().collect();
+        names.sort_by(|a, b| a.split(':').cmp(b.split(':')));
+
+        names
+            .into_iter()
+            .map(|name| {
+                let start: Option<Date> = (!is_balance_sheet_account(name)).then_some(range.start);
+                let mut totals: BTreeMap<CurrencyCode, i64> = BTreeMap::new();
+                let postings = self
+                    .transactions()
+                    .filter(|t| t.date < range.end && start.is_none_or(|start| t.date >= start))
+                    .flat_map(|t| &t.postings)
+                    .filter(|p| is_sub_account(&p.account, name));
+                for amount in postings.filter_map(|p| p.amount) {
+                    *totals.entry(amount.code()).or_default() += amount.value();
+                }
+
+                AccountNode {
+                    name: name.to_string(),
+                    depth: name.matches(':').count(),
+                    totals: totals
+                        .into_iter()
+                        .filter(|(_, value)| *value != 0)
+                        .map(|(code, value)| Amount::new(code, value))
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Processor;
+    use kakei_types::{
+        currency::{Amount, JPY},
+        date::Date,
+    };
+
+    #[test]
+    fn register_and_tree() -> anyhow::Result<()> {
+        let src = r#"
+2026-02-25 "Salary"
+  Assets:Bank       300000 JPY
+  Income:Salary
+
+2026-03-01 "Matsuya"
+  Expenses:Food     1200 JPY
+  Assets:Wallet
+
+2026-03-05 "ATM"
+  Assets:Wallet     10000 JPY
+  Assets:Bank
+
+2026-04-02 "Sukiya"
+  Expenses:Food:Lunch  500 JPY
+  Assets:Wallet
+"#;
+        let processor = Processor::from_entries(kakei_parser::parse(src)?)?;
+        let march = Date::new(2026, 3, 1)?..Date::new(2026, 4, 1)?;
+
+        let register = processor.register("Assets", march.clone());
+        assert_eq!(register.len(), 3);
+        assert_eq!(register[0].counterparts, vec!["Expenses:Food".to_string()]);
+        assert_eq!(register[2].balance, Amount::JPY(JPY(298800)));
+
+        let lunch = processor.register(
+            "Expenses:Food",
+            Date::new(2026, 4, 1)?..Date::new(2026, 5, 1)?,
+        );
+        assert_eq!(lunch.len(), 1);
+        assert_eq!(lunch[0].account, "Expenses:Food:Lunch");
+        assert_eq!(lunch[0].balance, Amount::JPY(JPY(1700)));
+
+        let tree = processor.account_tree(march);
+        let names: Vec<(&str, usize)> = tree.iter().map(|n| (n.name.as_str(), n.depth)).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Assets", 0),
+                ("Assets:Bank", 1),
+                ("Assets:Wallet", 1),
+                ("Expenses", 0),
+                ("Expenses:Food", 1),
+                ("Expenses:Food:Lunch", 2),
+                ("Income", 0),
+                ("Income:Salary", 1),
+            ]
+        );
+        assert_eq!(tree[0].totals, vec![Amount::JPY(JPY(298800))]);
+        assert_eq!(tree[4].totals, vec![Amount::JPY(JPY(1200))]);
+        assert!(tree[6].totals.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,265 @@
+//! ----- Suggestions for writing entries -----
+
+use crate::{Processor, ProcessorError, is_asset_account, is_sub_account};
+use kakei_parser::QuickEntry;
+use kakei_types::{
+    currency::{Amount, CurrencyCode},
+    date::Date,
+    entry::{Entry, Posting, Transaction},
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+impl Processor {
+    /// Every payee written in the notes, most recently used first.
+    pub fn payees(&self) -> Vec<&str> {
+        let mut payees: Vec<&str> = Vec::new();
+        for transaction in self.transactions().collect::<Vec<_>>().into_iter().rev() {
+            if !transaction.generated && !payees.contains(&transaction.payee.as_str()) {
+                payees.push(&transaction.payee);
+            }
+        }
+
+        payees
+    }
+
+    /// Every account opened, budgeted or posted to, sorted by name.
+    pub fn accounts(&self) -> Vec<&str> {
+        let mut accounts: BTreeSet<&str> = BTreeSet::new();
+        for entry in &self.entries {
+            match entry {
+                Entry::Open(open) => {
+                    accounts.insert(&open.account);
+                }
+                Entry::Budget(budget) => {
+                    accounts.insert(&budget.account);
+                }
+                Entry::Transaction(transaction) => {
+                    accounts.extend(transaction.postings.iter().map(|p| p.account.as_str()));
+                }
+                Entry::Recurring(recurring) => {
+                    accounts.extend(recurring.postings.iter().map(|p| p.account.as_str()));
+                }
+                Entry::Include(_) | Entry::Price(_) => {}
+            }
+        }
+
+        accounts.into_iter().collect()
+    }
+
+    /// The latest transaction written for `payee`.
+    pub fn last_transaction(&self, payee: &str) -> Option<&Transaction> {
+        self.transactions()
+            .filter(|t| !t.generated && t.payee == payee)
+            .last()
+    }
+
+    /// The account paying for `account` to `payee`: the one which paid the payee last time,
+    /// or else [`Processor::funding_account`].
+    pub fn paying_account(&self, payee: &str, account: &str) -> Option<String> {
+        let last = self.last_transaction(payee);
+        let last_account = last
+            .and_then(|t| t.postings.iter().find(|p| p.amount.is_some()))
+            .map(|p| p.account.as_str());
+
+        last.and_then(|t| {
+            t.postings
+                .iter()
+                .rfind(|p| p.account != account && Some(p.account.as_str()) != last_account)
+        })
+        .map(|p| p.account.clone())
+        .or_else(|| self.funding_account(account))
+    }
+
+    /// The asset account which paid for `account` most often.
+    pub fn funding_account(&self, account: &str) -> Option<String> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        let transactions = self.transactions().filter(|t| {
+            t.postings
+                .iter()
+                .any(|p| is_sub_account(&p.account, account))
+        });
+        for transaction in transactions {
+            for posting in &transaction.postings {
+                if is_asset_account(&posting.account) {
+                    *counts.entry(&posting.account).or_default() += 1;
+                }
+            }
+        }
+
+        counts
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .map(|(account, _)| account.to_string())
+    }
+}
+
+impl Processor {
+    /// Completes a one-line entry into a transaction dated `today` unless written.
+    ///
+    /// Omitted accounts and currency are taken from the latest transaction of the payee, and
+    /// the paying account falls back to [`Processor::funding_account`].
+    pub fn complete_quick(
+        &self,
+        quick: &QuickEntry,
+        today: Date,
+    ) -> Result<Transaction, ProcessorError> {
+        let payee: String = self
+            .payees()
+            .into_iter()
+            .find(|p| p.eq_ignore_ascii_case(&quick.payee))
+            .unwrap_or(&quick.payee)
+            .to_string();
+        let last = self.last_transaction(&payee);
+        let last_posting = last.and_then(|t| t.postings.iter().find(|p| p.amount.is_some()));
+
+        let account: String = match &quick.account {
+            Some(hint) => self.match_account(hint)?,
+            None => last_posting
+                .map(|p| p.account.clone())
+                .ok_or_else(|| ProcessorError::NoAccount(payee.clone()))?,
+        };
+        let from: String = match &quick.from {
+            Some(hint) => self.match_account(hint)?,
+            None => self
+                .paying_account(&payee, &account)
+                .ok_or_else(|| ProcessorError::NoAccount(payee.clone()))?,
+        };
+        let currency: CurrencyCode = quick
+            .currency
+            .or_else(|| last_posting.and_then(|p| p.amount).map(|a| a.code()))
+            .unwrap_or(CurrencyCode::JPY);
+
+        Ok(Transaction {
+            date: quick.date.unwrap_or(today),
+            payee,
+            postings: vec![
+                Posting {
+                    account,
+                    amount: Some(Amount::new(currency, quick.value)),
+                    cost: None,
+                },
+                Posting {
+                    account: from,
+                    amount: None,
+                    cost: None,
+                },
+            ],
+            metadata: Vec::new(),
+            generated: false,
+        })
+    }
+
+    /// The account `hint` refers to, ignoring case: the whole name, its last segment, the
+    /// start of a segment, then any part of it.
+    fn match_account(&self, hint: &str) -> Result<String, ProcessorError> {
+        let hint_lower = hint.to_lowercase();
+        let accounts: Vec<(&str, String)> = self
+            .accounts()
+            .into_iter()
+            .map(|a| (a, a.to_lowercase()))
+            .collect();
+        let find = |matches: &dyn Fn(&str) -> bool| {
+            accounts
+                .iter()
+                .find(|(_, lower)| matches(lower))
+                .map(|(account, _)| account.to_string())
+        };
+
+        find(&|a| a == hint_lower)
+            .or_else(|| find(&|a| a.rsplit(':').next() == Some(&hint_lower)))
+            .or_else(|| find(&|a| a.split(':').any(|s| s.starts_with(&hint_lower))))
+            .or_else(|| find(&|a| a.contains(&hint_lower)))
+            .ok_or_else(|| ProcessorError::UnknownAccount(hint.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Processor;
+    use kakei_types::{currency::CurrencyCode, date::Date};
+
+    #[test]
+    fn suggestions() -> anyhow::Result<()> {
+        let src = "\
+2026-04-01 open Assets:Wallet JPY
+2026-04-01 \"Matsuya\"
+  Expenses:Food  780 JPY
+  Assets:Bank
+2026-04-03 \"Doutor\"
+  Expenses:Cafe  450 JPY
+  Assets:Wallet
+2026-04-05 \"Matsuya\"
+  Expenses:Food  980 JPY
+  Assets:Wallet
+";
+        let processor = Processor::from_entries(kakei_parser::parse(src)?)?;
+
+        assert_eq!(processor.payees(), vec!["Matsuya", "Doutor"]);
+        assert_eq!(
+            processor.accounts(),
+            vec![
+                "Assets:Bank",
+                "Assets:Wallet",
+                "Expenses:Cafe",
+                "Expenses:Food"
+            ]
+        );
+        let last = processor.last_transaction("Matsuya").map(|t| t.date.day());
+        assert_eq!(last, Some(5));
+        assert_eq!(
+            processor.funding_account("Expenses"),
+            Some("Assets:Wallet".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn complete_quick_entry() -> anyhow::Result<()> {
+        let src = "\
+2026-04-01 \"Matsuya\"
+  Expenses:Food  780 JPY
+  Assets:Wallet
+2026-04-02 \"Bitcoin cafe\"
+  Expenses:Cafe  2100 SATS
+  Assets:Lightning
+";
+        let processor = Processor::from_entries(kakei_parser::parse(src)?)?;
+        let today = Date::new(2026, 4, 10)?;
+
+        let matsuya =
+            processor.complete_quick(&kakei_parser::parse_quick("matsuya 980")?, today)?;
+        assert_eq!(
+            matsuya.to_string(),
+            "2026-04-10 \"Matsuya\"\n  Expenses:Food  980 JPY\n  Assets:Wallet\n"
+        );
+
+        let cafe = kakei_parser::parse_quick("Bitcoin cafe 1500 food")?;
+        let cafe = processor.complete_quick(&cafe, today)?;
+        assert_eq!(cafe.postings[0].account, "Expenses:Food");
+        assert_eq!(
+            cafe.postings[0].amount.map(|a| a.code()),
+            Some(CurrencyCode::SATS)
+        );
+        assert_eq!(cafe.postings[1].account, "Assets:Lightning");
+
+        assert!(
+            processor
+                .complete_quick(&kakei_parser::parse_quick("lunch 980")?, today)
+                .is_err()
+        );
+        assert!(
+            processor
+                .complete_quick(&kakei_parser::parse_quick("lunch 980 rent")?, today)
+                .is_err()
+        );
+        assert!(
+            processor
+                .complete_quick(
+                    &kakei_parser::parse_quick("lunch 980 Expenses:Lunch")?,
+                    today
+                )
+                .is_err()
+        );
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 //! Currency module
 
 use std::ops::{Add, Sub};
+use thiserror::Error;
 
 pub trait Currency:
     Add<Output = Self> + Sub<Output = Self> + Sized + Copy + std::fmt::Display
@@ -68,3 +69,126 @@ impl std::ops::Sub for SATS {
         Self(inner)
     }
 }
+
+// ----- Currency Code -----
+
+/// Currency code written in kakeibo notes, such as `JPY`.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum CurrencyCode {
+    JPY,
+    SATS,
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("unknown currency: {0}")]
+pub struct UnknownCurrency(pub String);
+
+impl std::fmt::Display for CurrencyCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::JPY => write!(f, "JPY"),
+            Self::SATS => write!(f, "SATS"),
+        }
+    }
+}
+
+impl std::str::FromStr for CurrencyCode {
+    type Err = UnknownCurrency;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "JPY" => Ok(Self::JPY),
+            "SATS" => Ok(Self::SATS),
+            v => Err(UnknownCurrency(v.to_string())),
+        }
+    }
+}
+
+// ----- Amount -----
+
+/// An amount of any supported currency.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Amount {
+    JPY(JPY),
+    SATS(SATS),
+}
+
+impl Amount {
+    pub fn new(code: CurrencyCode, value: i64) -> Self {
+        match code {
+            CurrencyCode::JPY => Self::JPY(JPY(value)),
+            CurrencyCode::SATS => Self::SATS(SATS(value)),
+        }
+    }
+
+    pub fn code(&self) -> CurrencyCode {
+        match self {
+            Self::JPY(_) => CurrencyCode::JPY,
+            Self::SATS(_) => CurrencyCode::SATS,
+        }
+    }
+
+    pub fn value(&self) -> i64 {
+        match self {
+            Self::JPY(v) => v.0,
+            Self::SATS(v) => v.0,
+        }
+    }
+}
+
+impl std::fmt::Display for Amount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::JPY(v) => v.fmt(f),
+            Self::SATS(v) => v.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AmountError {
+    #[error("invalid amount: \"{0}\", expected a number such as 980 or 1,500 JPY")]
+    Format(String),
+
+    #[error(transparent)]
+    Currency(#[from] UnknownCurrency),
+}
+
+/// `980`, `-1,500 JPY` or `21000 SATS`. A number without a currency is JPY.
+impl std::str::FromStr for Amount {
+    type Err = AmountError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let number = words.next().unwrap_or_default().replace(',', "");
+        let code: CurrencyCode = match words.next() {
+            Some(code) => code.parse()?,
+            None => CurrencyCode::JPY,
+        };
+        if words.next().is_some() {
+            return Err(AmountError::Format(s.to_string()));
+        }
+
+        let value: i64 = number
+            .parse()
+            .map_err(|_| AmountError::Format(s.to_string()))?;
+        Ok(Self::new(code, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Amount, JPY, SATS};
+
+    #[test]
+    fn parse_amount() -> anyhow::Result<()> {
+        assert_eq!("980".parse::<Amount>()?, Amount::JPY(JPY(980)));
+        assert_eq!("-1,500 JPY".parse::<Amount>()?, Amount::JPY(JPY(-1500)));
+        assert_eq!("21000 SATS".parse::<Amount>()?, Amount::SATS(SATS(21000)));
+        assert!("980 USD".parse::<Amount>().is_err());
+        assert!("lunch".parse::<Amount>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,273 @@
+//! Date module
+
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// A calendar date without any time zone, such as `2026-03-14`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum DateError {
+    #[error("invalid date: {0}-{1:02}-{2:02}")]
+    OutOfRange(i32, u32, u32),
+
+    #[error("invalid date format: \"{0}\", expected YYYY-MM-DD")]
+    Format(String),
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> Result<Self, DateError> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return Err(DateError::OutOfRange(year, month, day));
+        }
+
+        Ok(Self { year, month, day })
+    }
+
+    /// Today's date in the local time zone, so a purchase just after midnight in Japan is
+    /// recorded on the day it was made instead of the day before in UTC.
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        Self::from_days((seconds + utc_offset(seconds)).div_euclid(86_400))
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// Number of days since 1970-01-01.
+    pub fn to_days(&self) -> i64 {
+        // Howard Hinnant's days_from_civil
+        let y = if self.month <= 2 {
+            self.year as i64 - 1
+        } else {
+            self.year as i64
+        };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let m = self.month as i64;
+        let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+        era * 146_097 + doe - 719_468
+    }
+
+    /// Inverse of [`Date::to_days`].
+    pub fn from_days(days: i64) -> Self {
+        // Howard Hinnant's civil_from_days
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+
+        Self { year, month, day }
+    }
+
+    pub fn add_days(&self, days: i64) -> Self {
+        Self::from_days(self.to_days() + days)
+    }
+
+    /// Moves by whole months, clamping the day to the end of the target month.
+    pub fn add_months(&self, months: i32) -> Self {
+        let index = self.year * 12 + self.month as i32 - 1 + months;
+        let year = index.div_euclid(12);
+        let month = index.rem_euclid(12) as u32 + 1;
+        let day = self.day.min(days_in_month(year, month));
+
+        Self { year, month, day }
+    }
+}
+
+/// How often a budget or a recurring entry repeats from its anchor date.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Period {
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("unknown period: \"{0}\", expected weekly, monthly or yearly")]
+pub struct UnknownPeriod(pub String);
+
+impl Period {
+    /// The period counted from `anchor` which contains `date`, as `start..end`.
+    pub fn containing(&self, anchor: Date, date: Date) -> std::ops::Range<Date> {
+        match self {
+            Self::Weekly => {
+                let weeks = (date.to_days() - anchor.to_days()).div_euclid(7);
+                let start = anchor.add_days(weeks * 7);
+                start..start.add_days(7)
+            }
+            Self::Monthly => {
+                let mut months =
+                    (date.year - anchor.year) * 12 + date.month as i32 - anchor.month as i32;
+                if anchor.add_months(months) > date {
+                    months -= 1;
+                }
+                anchor.add_months(months)..anchor.add_months(months + 1)
+            }
+            Self::Yearly => {
+                let mut years = date.year - anchor.year;
+                if anchor.add_months(years * 12) > date {
+                    years -= 1;
+                }
+                anchor.add_months(years * 12)..anchor.add_months((years + 1) * 12)
+            }
+        }
+    }
+
+    /// The `n`th repetition counted from `anchor`, which is the 0th.
+    pub fn nth(&self, anchor: Date, n: i32) -> Date {
+        match self {
+            Self::Weekly => anchor.add_days(n as i64 * 7),
+            Self::Monthly => anchor.add_months(n),
+            Self::Yearly => anchor.add_months(n * 12),
+        }
+    }
+}
+
+impl std::fmt::Display for Period {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Weekly => write!(f, "weekly"),
+            Self::Monthly => write!(f, "monthly"),
+            Self::Yearly => write!(f, "yearly"),
+        }
+    }
+}
+
+impl std::str::FromStr for Period {
+    type Err = UnknownPeriod;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "weekly" => Ok(Self::Weekly),
+            "monthly" => Ok(Self::Monthly),
+            "yearly" => Ok(Self::Yearly),
+            v => Err(UnknownPeriod(v.to_string())),
+        }
+    }
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+/// Seconds the local time zone is ahead of UTC at `seconds` after the epoch, by the `TZ` of the
+/// process or the zone of the system.
+#[cfg(unix)]
+fn utc_offset(seconds: i64) -> i64 {
+    let time: libc::time_t = seconds as libc::time_t;
+    let mut local = std::mem::MaybeUninit::<libc::tm>::uninit();
+    // SAFETY: `localtime_r` writes `local` and returns it, or null without writing anything
+    let local = unsafe {
+        if libc::localtime_r(&time, local.as_mut_ptr()).is_null() {
+            return 0;
+        }
+        local.assume_init()
+    };
+
+    local.tm_gmtoff as i64
+}
+
+/// UTC where the local time zone is not known
+#[cfg(not(unix))]
+fn utc_offset(_seconds: i64) -> i64 {
+    0
+}
+
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl std::str::FromStr for Date {
+    type Err = DateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let format_error = || DateError::Format(s.to_string());
+        let mut parts = s.splitn(3, '-');
+        let mut next = || -> Result<&str, DateError> {
+            parts
+                .next()
+                .filter(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+                .ok_or_else(format_error)
+        };
+
+        let year = next()?.parse().map_err(|_| format_error())?;
+        let month = next()?.parse().map_err(|_| format_error())?;
+        let day = next()?.parse().map_err(|_| format_error())?;
+
+        Self::new(year, month, day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Date, Period};
+
+    #[test]
+    fn days_round_trip() -> anyhow::Result<()> {
+        let date: Date = "2024-02-29".parse()?;
+        assert_eq!(Date::from_days(date.to_days()), date);
+        assert_eq!(Date::new(1970, 1, 1)?.to_days(), 0);
+        assert_eq!(date.add_days(1), Date::new(2024, 3, 1)?);
+        Ok(())
+    }
+
+    #[test]
+    fn add_months_clamps_day() -> anyhow::Result<()> {
+        let date = Date::new(2026, 1, 31)?;
+        assert_eq!(date.add_months(1), Date::new(2026, 2, 28)?);
+        assert_eq!(date.add_months(-2), Date::new(2025, 11, 30)?);
+        Ok(())
+    }
+
+    #[test]
+    fn period_containing() -> anyhow::Result<()> {
+        let payday = Date::new(2026, 1, 25)?;
+        let monthly = Period::Monthly.containing(payday, Date::new(2026, 4, 10)?);
+        assert_eq!(monthly, Date::new(2026, 3, 25)?..Date::new(2026, 4, 25)?);
+
+        let monday = Date::new(2026, 4, 6)?;
+        let weekly = Period::Weekly.containing(monday, Date::new(2026, 4, 1)?);
+        assert_eq!(weekly, Date::new(2026, 3, 30)?..Date::new(2026, 4, 6)?);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_dates() {
+        assert!("2026-02-29".parse::<Date>().is_err());
+        assert!("2026-3-1x".parse::<Date>().is_err());
+    }
+}
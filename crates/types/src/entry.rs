@@ -0,0 +1,207 @@
+//! Entries written in kakeibo notes
+
+use crate::{
+    currency::{Amount, CurrencyCode},
+    date::{Date, Period},
+    rate::Rate,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Entry {
+    Include(Include),
+    Open(Open),
+    Budget(Budget),
+    Price(Price),
+    Transaction(Transaction),
+    Recurring(Recurring),
+}
+
+impl Entry {
+    /// `None` for entries which are not dated, such as `include`.
+    pub fn date(&self) -> Option<Date> {
+        match self {
+            Self::Include(_) => None,
+            Self::Open(v) => Some(v.date),
+            Self::Budget(v) => Some(v.date),
+            Self::Price(v) => Some(v.date),
+            Self::Transaction(v) => Some(v.date),
+            Self::Recurring(v) => Some(v.start),
+        }
+    }
+}
+
+/// `include "prices.kakei"`, resolved relative to the including file.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Include {
+    pub path: String,
+}
+
+/// `2026-01-01 open Assets:Wallet JPY`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Open {
+    pub date: Date,
+    pub account: String,
+    pub currencies: Vec<CurrencyCode>,
+}
+
+impl std::fmt::Display for Open {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} open {}", self.date, self.account)?;
+        for currency in &self.currencies {
+            write!(f, " {currency}")?;
+        }
+        Ok(())
+    }
+}
+
+/// `2026-04 budget Expenses:Food 60000 JPY`, or `2026-04-06 budget weekly ...`
+///
+/// A budget repeats every `period` from `date` until a later budget of the same account.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Budget {
+    pub date: Date,
+    pub period: Period,
+    pub account: String,
+    pub amount: Amount,
+}
+
+/// `2026-03-14 price SATS 0.0145 JPY`, one `base` is worth `rate` of `quote`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Price {
+    pub date: Date,
+    pub base: CurrencyCode,
+    pub rate: Rate,
+    pub quote: CurrencyCode,
+}
+
+impl std::fmt::Display for Price {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} price {} {} {}",
+            self.date, self.base, self.rate, self.quote
+        )
+    }
+}
+
+/// ```text
+/// 2026-03-14 "Matsuya"
+///   Expenses:Food  980 JPY
+///   Assets:Wallet
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Transaction {
+    pub date: Date,
+    pub payee: String,
+    pub postings: Vec<Posting>,
+
+    /// `; ref: 20260314-0001` comment lines, such as the reference of an imported statement line
+    pub metadata: Vec<(String, String)>,
+
+    /// Generated from a [`Recurring`] template instead of written in the note
+    pub generated: bool,
+}
+
+impl Transaction {
+    /// The value of the metadata `key`
+    pub fn metadata(&self, key: &str) -> Option<&str> {
+        self.metadata
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl std::fmt::Display for Transaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} \"{}\"", self.date, self.payee)?;
+        for (key, value) in &self.metadata {
+            writeln!(f, "  ; {key}: {value}")?;
+        }
+        for posting in &self.postings {
+            writeln!(f, "  {}", posting)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Posting {
+    pub account: String,
+
+    /// `None` when the amount is left for the processor to balance.
+    pub amount: Option<Amount>,
+
+    /// `{1450 JPY}`, the whole value paid for or received by `amount`.
+    ///
+    /// It is written without a sign, and the transaction balances with it instead of `amount`.
+    pub cost: Option<Amount>,
+}
+
+impl std::fmt::Display for Posting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.account)?;
+        if let Some(amount) = self.amount {
+            write!(f, "  {}", amount)?;
+        }
+        if let Some(cost) = self.cost {
+            write!(f, " {{{}}}", cost)?;
+        }
+        Ok(())
+    }
+}
+
+impl Posting {
+    /// The amount this posting contributes to the balance of its transaction.
+    pub fn weight(&self) -> Option<Amount> {
+        match (self.amount, self.cost) {
+            (Some(amount), Some(cost)) => Some(Amount::new(
+                cost.code(),
+                cost.value().abs() * amount.value().signum(),
+            )),
+            (amount, _) => amount,
+        }
+    }
+}
+
+/// ```text
+/// ~ monthly from 2026-01-25 "Rent"
+///   Expenses:Rent  85000 JPY
+///   Assets:Bank
+/// ```
+///
+/// The first posting may also be written on the first line, as in
+/// `~ monthly from 2026-01-25 Expenses:Rent 85000 JPY`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Recurring {
+    pub period: Period,
+    pub start: Date,
+
+    /// The last date an occurrence may fall on
+    pub until: Option<Date>,
+    pub payee: String,
+    pub postings: Vec<Posting>,
+}
+
+impl Recurring {
+    /// Every occurrence in `range`.
+    pub fn occurrences(&self, range: std::ops::Range<Date>) -> impl Iterator<Item = Date> + '_ {
+        (0..)
+            .map(|n| self.period.nth(self.start, n))
+            .take_while(move |date| {
+                *date < range.end && self.until.is_none_or(|until| *date <= until)
+            })
+            .filter(move |date| *date >= range.start)
+    }
+
+    /// The transaction occurring on `date`, marked as generated.
+    pub fn transaction(&self, date: Date) -> Transaction {
+        Transaction {
+            date,
+            payee: self.payee.clone(),
+            postings: self.postings.clone(),
+            metadata: Vec::new(),
+            generated: true,
+        }
+    }
+}
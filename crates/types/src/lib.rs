@@ -1,4 +1,7 @@
 //! Types crate
 
 pub mod currency;
+pub mod date;
+pub mod entry;
 pub mod query;
+pub mod rate;
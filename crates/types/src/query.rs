@@ -1,4 +1,7 @@
-use crate::currency::Currency;
+use crate::{
+    currency::{Amount, Currency, JPY},
+    date::Date,
+};
 use tabled::Tabled;
 
 #[derive(Debug, Tabled, Clone)]
@@ -11,3 +14,65 @@ where
     pub credit: C,
     pub total: C,
 }
+
+/// A balance converted into another currency.
+#[derive(Debug, Tabled, Clone)]
+pub struct Valuation {
+    pub name: String,
+    pub balance: Amount,
+    pub value: Amount,
+}
+
+/// Disposal of a currency held as an asset, such as spending SATS.
+#[derive(Debug, Tabled, Clone, PartialEq)]
+pub struct Disposal {
+    pub date: Date,
+    pub payee: String,
+    pub units: Amount,
+    pub proceeds: JPY,
+    pub cost_basis: JPY,
+    pub gain: JPY,
+}
+
+/// Actual spending of an account against its budget in one period.
+#[derive(Debug, Tabled, Clone, PartialEq)]
+pub struct BudgetQuery {
+    pub name: String,
+    pub from: Date,
+    pub to: Date,
+    pub budgeted: Amount,
+    pub actual: Amount,
+    pub remaining: Amount,
+    pub used: Percent,
+}
+
+impl BudgetQuery {
+    pub fn is_over(&self) -> bool {
+        self.remaining.value() < 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Percent(pub f64);
+
+impl Percent {
+    /// `None` when `whole` is zero.
+    pub fn of(part: i64, whole: i64) -> Option<Self> {
+        (whole != 0).then(|| Self(part as f64 * 100.0 / whole as f64))
+    }
+}
+
+impl std::fmt::Display for Percent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.1}%", self.0)
+    }
+}
+
+/// One line of the kakeibo reflection, compared with the previous month.
+#[derive(Debug, Tabled, Clone, PartialEq)]
+pub struct ReflectionQuery {
+    pub name: String,
+    pub this_month: JPY,
+    pub last_month: JPY,
+    pub change: JPY,
+}
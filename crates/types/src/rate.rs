@@ -0,0 +1,160 @@
+//! Rate module
+
+use thiserror::Error;
+
+/// A positive decimal exchange rate, such as `0.0145`.
+///
+/// The rate is kept as the digits written in the note and the number of decimal places,
+/// so it can be printed back without loss.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Rate {
+    mantissa: i64,
+    scale: u32,
+}
+
+/// Most decimal places of a rate, so `10^scale` always fits in an `i64`
+pub const MAX_SCALE: u32 = 18;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum RateError {
+    #[error("invalid rate: \"{0}\"")]
+    Format(String),
+
+    #[error("rate must be positive: \"{0}\"")]
+    NotPositive(String),
+
+    #[error("rate has more than {MAX_SCALE} decimal places: \"{0}\"")]
+    TooPrecise(String),
+
+    #[error("{value} converted at {rate} does not fit in an amount")]
+    Overflow { value: i64, rate: Rate },
+}
+
+impl Rate {
+    /// Converts `value` of the base currency into the quote currency, rounding half away from zero.
+    pub fn convert(&self, value: i64) -> Result<i64, RateError> {
+        let overflow = || RateError::Overflow { value, rate: *self };
+        let numerator = (value as i128)
+            .checked_mul(self.mantissa as i128)
+            .ok_or_else(overflow)?;
+        let denominator = 10i128.checked_pow(self.scale).ok_or_else(overflow)?;
+
+        i64::try_from(div_round(numerator, denominator)).map_err(|_| overflow())
+    }
+
+    /// Converts `value` of the quote currency back into the base currency.
+    pub fn convert_inverse(&self, value: i64) -> Result<i64, RateError> {
+        let overflow = || RateError::Overflow { value, rate: *self };
+        let numerator = 10i128
+            .checked_pow(self.scale)
+            .and_then(|divisor| (value as i128).checked_mul(divisor))
+            .ok_or_else(overflow)?;
+
+        i64::try_from(div_round(numerator, self.mantissa as i128)).map_err(|_| overflow())
+    }
+}
+
+fn div_round(numerator: i128, denominator: i128) -> i128 {
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    if remainder.abs() * 2 >= denominator.abs() {
+        quotient + numerator.signum() * denominator.signum()
+    } else {
+        quotient
+    }
+}
+
+impl std::fmt::Display for Rate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // `from_str` keeps the scale within MAX_SCALE
+        let divisor = 10i64.pow(self.scale);
+        let integer = self.mantissa / divisor;
+        let fraction = self.mantissa % divisor;
+
+        if self.scale == 0 {
+            write!(f, "{}", integer)
+        } else {
+            write!(
+                f,
+                "{}.{:0width$}",
+                integer,
+                fraction,
+                width = self.scale as usize
+            )
+        }
+    }
+}
+
+impl std::str::FromStr for Rate {
+    type Err = RateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let format_error = || RateError::Format(s.to_string());
+        let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let fraction = fraction.trim_end_matches('0');
+
+        if integer.is_empty()
+            || !integer
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(format_error());
+        }
+
+        let scale = fraction.len() as u32;
+        if scale > MAX_SCALE {
+            return Err(RateError::TooPrecise(s.to_string()));
+        }
+        let mantissa: i64 = format!("{integer}{fraction}")
+            .parse()
+            .map_err(|_| format_error())?;
+        if mantissa == 0 {
+            return Err(RateError::NotPositive(s.to_string()));
+        }
+
+        Ok(Self { mantissa, scale })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rate, RateError};
+
+    #[test]
+    fn convert() -> anyhow::Result<()> {
+        let rate: Rate = "0.0145".parse()?;
+        assert_eq!(rate.to_string(), "0.0145");
+        assert_eq!(rate.convert(100_000)?, 1450);
+        assert_eq!(rate.convert(-35)?, -1);
+        assert_eq!(rate.convert_inverse(1450)?, 100_000);
+        Ok(())
+    }
+
+    #[test]
+    fn bounds() -> anyhow::Result<()> {
+        let smallest: Rate = "0.000000000000000001".parse()?;
+        assert_eq!(smallest.to_string(), "0.000000000000000001");
+        assert_eq!(smallest.convert(i64::MAX)?, 9);
+        assert!(smallest.convert_inverse(i64::MAX).is_err());
+        assert!(matches!(
+            "0.0000000000000000001".parse::<Rate>(),
+            Err(RateError::TooPrecise(_))
+        ));
+
+        let large: Rate = "1000".parse()?;
+        assert!(matches!(
+            large.convert(i64::MAX),
+            Err(RateError::Overflow { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn normalizes_trailing_zeros() -> anyhow::Result<()> {
+        assert_eq!("150.00".parse::<Rate>()?.to_string(), "150");
+        assert!("0".parse::<Rate>().is_err());
+        assert!("-1".parse::<Rate>().is_err());
+        Ok(())
+    }
+}